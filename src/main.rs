@@ -3,15 +3,18 @@ extern crate antgine;
 use std::{
     mem,
     thread::{sleep, self},
-    time::{Duration, Instant}, sync::mpsc, net::TcpListener, io::Write,
+    time::{Duration, Instant}, sync::mpsc, net::TcpListener, io::{BufRead, BufReader, Write},
 };
 
 use antgine::physics_engine::{
-    map::Map, objects::rectangle::Rectangle, traits::object_interface::ObjectInterface,
-    types::vec2d::Vec2D,
+    joints::{mouse::MouseJoint, JointHandle}, map::Map, objects::rectangle::Rectangle,
+    solver::ObjectId, traits::object_interface::ObjectInterface, types::vec2d::Vec2D,
 };
 use serde_json::{Value, json};
 
+/// The largest acceleration with which the mouse joint pulls the grabbed object, so that heavy objects are dragged as easily as light ones
+const GRAB_ACCELERATION: f32 = 1000.0;
+
 enum Program {
     Start,
     _Working,
@@ -21,6 +24,7 @@ enum Program {
 fn main() {
     let (tx_ready, rx_ready) = mpsc::channel();
     let (tx_data, rx_data) = mpsc::channel();
+    let (tx_command, rx_command) = mpsc::channel::<Value>();
 
     let mut handles = Vec::with_capacity(2);
     handles.push(thread::spawn(move || {
@@ -28,7 +32,6 @@ fn main() {
     let mut map = Map::default();
     map.init_map_border(Vec2D::new(0.0, 0.0), Vec2D::new(1920.0, 1080.0));
     // map.test_rectangle();
-    let a = Rectangle::default();
     let a = Rectangle::new(
         Vec2D::new(1910.0, 0.0),
         Vec2D::new(1910.0, 20.0),
@@ -38,7 +41,7 @@ fn main() {
         Vec2D::new(-100.0, 0.0),
         0.01,
         0.0,
-        0.01,
+        0.6,
     );
     // let b = Rectangle::new(
     //     Vec2D::new(1900.0, 0.0),
@@ -49,32 +52,91 @@ fn main() {
     //     Vec2D::new(-100.0, 0.0),
     //     0.01,
     //     0.0,
-    //     0.01,
+    //     0.6,
     // );
-    println!("{:?} {:?}", (&a).get_current_position(), (&a).get_size());
+    println!("{:?} {:?}", a.get_current_position(), a.get_size());
     println!("{}", mem::size_of_val(&a));
     map.dyn_objects.push(Box::new(a));
     // map.dyn_objects.push(Box::new(b));
 
     let mut ready = false;
+    let mut mouse_joint: Option<JointHandle> = None;
+    let mut last_time = Instant::now();
     loop {
-        let now = Instant::now();
         sleep(Duration::from_millis(10));
-        map.run(Instant::now().duration_since(now).as_secs_f32());
+
+        // the client drags objects with the mouse joint
+        while let Ok(command) = rx_command.try_recv() {
+            if let Some(grab) = command.get("Grab") {
+                // malformed commands are ignored
+                let (Some(object), Some(x), Some(y)) = (
+                    grab["object"].as_u64(),
+                    grab["point"][0].as_f64(),
+                    grab["point"][1].as_f64(),
+                ) else {
+                    continue;
+                };
+                let object = object as usize;
+                let point = Vec2D::new(x as f32, y as f32);
+                if object < map.dyn_objects.len() {
+                    if let Some(handle) = mouse_joint.take() {
+                        map.remove_joint(handle);
+                    }
+
+                    let local_anchor = map.get_local_point(ObjectId::Dynamic(object), point);
+                    let max_force = GRAB_ACCELERATION * map.dyn_objects[object].get_mass();
+                    mouse_joint = Some(map.add_joint(Box::new(MouseJoint::new(
+                        ObjectId::Dynamic(object),
+                        local_anchor,
+                        point,
+                        max_force,
+                    ))));
+                }
+            } else if let Some(drag) = command.get("Drag") {
+                let (Some(x), Some(y)) = (drag["target"][0].as_f64(), drag["target"][1].as_f64()) else {
+                    continue;
+                };
+                let target = Vec2D::new(x as f32, y as f32);
+                if let Some(joint) = mouse_joint.and_then(|handle| map.get_mut_joint::<MouseJoint>(handle)) {
+                    joint.set_target(target);
+                }
+            } else if command.get("Release").is_some() {
+                if let Some(handle) = mouse_joint.take() {
+                    map.remove_joint(handle);
+                }
+            }
+        }
+
+        let now = Instant::now();
+        map.step(now.duration_since(last_time).as_secs_f32());
+        last_time = now;
 
         if let Ok(answer) = rx_ready.try_recv() {
             ready = answer;
         }
 
         if ready {
-            let (pos, ang) = map.create_data();
+            let (pos, ang, outlines) = map.create_interpolated_data();
             let pos: Vec<(f32, f32)> = pos.into_iter().map(|vec2d| (vec2d.x, vec2d.y)).collect();
             let ang: Vec<f32> = ang.into_iter().map(|angle| angle.get_radian()).collect();
+            let outlines: Vec<Vec<(f32, f32)>> = outlines
+                .into_iter()
+                .map(|outline| outline.into_iter().map(|vec2d| (vec2d.x, vec2d.y)).collect())
+                .collect();
+            let particles: Vec<Vec<(f32, f32)>> = map
+                .create_particle_data()
+                .into_iter()
+                .map(|particles| particles.into_iter().map(|vec2d| (vec2d.x, vec2d.y)).collect())
+                .collect();
             
             let json_obj: Value = json!({
                 "Ant": {
                     "pos": pos,
                     "angle": ang,
+                    "outline": outlines,
+                },
+                "Particles": {
+                    "pos": particles,
                 }
             });
 
@@ -97,6 +159,22 @@ fn main() {
                 Ok(mut stream) => {
                     println!("Connected to client");
                     tx_ready.send(true).unwrap();
+
+                    // commands of the client come as json objects, one per line
+                    let tx_command = tx_command.clone();
+                    let reader = BufReader::new(stream.try_clone().expect("Failed to clone stream"));
+                    thread::spawn(move || {
+                        for line in reader.lines().map_while(Result::ok) {
+                            match serde_json::from_str::<Value>(&line) {
+                                Ok(command) => {
+                                    if tx_command.send(command).is_err() {
+                                        break;
+                                    }
+                                }
+                                Err(e) => eprintln!("failed to parse command; err = {:?}", e),
+                            }
+                        }
+                    });
                     
                     loop {
                         let data = rx_data.recv().unwrap();
@@ -0,0 +1,375 @@
+use super::{
+    map::Map,
+    traits::object_interface::ObjectInterface,
+    types::{angle::Angle, vec2d::Vec2D},
+};
+
+/// Kinematic controller that moves a shape by the desired displacement, e.g. a player-controlled ant
+///
+/// The shape is cast against the objects of the map and stops at them instead of pushing them. It slides along walls,
+/// walks up slopes not steeper than the max slope and steps up on ledges not higher than the step height
+pub struct CharacterController {
+    shape: Box<dyn ObjectInterface>,
+    up: Vec2D,
+    max_slope: Angle,
+    step_height: f32,
+    skin: f32,
+    max_iterations: usize,
+    grounded: bool,
+    ceiling: bool,
+    wall: bool,
+    ground_normal: Vec2D,
+}
+
+impl CharacterController {
+    /// Creating a character controller of the shape, the shape is not added to the map
+    pub fn new(shape: Box<dyn ObjectInterface>) -> Self {
+        CharacterController {
+            shape,
+            up: Vec2D::new(0.0, -1.0),
+            max_slope: Angle::new(std::f32::consts::FRAC_PI_4),
+            step_height: 0.0,
+            skin: 0.1,
+            max_iterations: 4,
+            grounded: false,
+            ceiling: false,
+            wall: false,
+            ground_normal: Vec2D::default(),
+        }
+    }
+
+    pub fn get_shape(&self) -> &dyn ObjectInterface {
+        self.shape.as_ref()
+    }
+
+    /// Moves the shape to the position without checking for obstacles
+    pub fn set_position(&mut self, position: Vec2D) {
+        self.translate(position - self.shape.get_current_position());
+    }
+
+    pub fn get_position(&self) -> Vec2D {
+        self.shape.get_current_position()
+    }
+
+    /// Direction against the gravity, surfaces facing it are the ground
+    pub fn set_up(&mut self, up: Vec2D) {
+        self.up = up.unit();
+    }
+
+    pub fn get_up(&self) -> Vec2D {
+        self.up
+    }
+
+    /// The steepest slope on which the character stands and walks, steeper slopes are walls
+    pub fn set_max_slope(&mut self, max_slope: Angle) {
+        self.max_slope = max_slope;
+    }
+
+    pub fn get_max_slope(&self) -> Angle {
+        self.max_slope
+    }
+
+    /// The highest ledge on which the character steps up while walking, zero disables the step-ups
+    pub fn set_step_height(&mut self, step_height: f32) {
+        self.step_height = step_height;
+    }
+
+    pub fn get_step_height(&self) -> f32 {
+        self.step_height
+    }
+
+    /// Gap that the character keeps between its shape and the objects, so that it doesn't get stuck in them
+    pub fn set_skin(&mut self, skin: f32) {
+        self.skin = skin;
+    }
+
+    pub fn get_skin(&self) -> f32 {
+        self.skin
+    }
+
+    /// The largest number of surfaces along which the character slides during one move
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations.max(1);
+    }
+
+    pub fn get_max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Returns true if the character stood on the ground after the last move
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// Returns true if the character hit a ceiling during the last move
+    pub fn is_on_ceiling(&self) -> bool {
+        self.ceiling
+    }
+
+    /// Returns true if the character hit a wall during the last move
+    pub fn is_on_wall(&self) -> bool {
+        self.wall
+    }
+
+    /// Returns the normal of the ground under the character after the last move
+    pub fn get_ground_normal(&self) -> Vec2D {
+        self.ground_normal
+    }
+
+    /// Moves the character by the displacement, sliding along the objects of the map that stop it
+    ///
+    /// Returns the displacement that the character has actually passed
+    pub fn move_by(&mut self, map: &Map, displacement: Vec2D) -> Vec2D {
+        let start = self.get_position();
+        let was_grounded = self.grounded;
+        let jumping = Vec2D::dot(&displacement, &self.up) > 0.0;
+        self.grounded = false;
+        self.ceiling = false;
+        self.wall = false;
+        self.ground_normal = Vec2D::default();
+
+        let mut remaining = displacement;
+        for _ in 0..self.max_iterations {
+            if remaining.len_vector(&Vec2D::default()) <= f32::EPSILON {
+                break;
+            }
+
+            let (moved, normal) = self.sweep(map, remaining);
+            self.translate(moved);
+            remaining -= moved;
+            let normal = match normal {
+                Some(normal) => normal,
+                None => break,
+            };
+
+            let vertical = Vec2D::dot(&remaining, &self.up);
+            let horizontal = remaining - self.up.mul_n(vertical);
+            if self.is_walkable(&normal) {
+                // the character doesn't slide down the slopes on which it stands
+                self.grounded = true;
+                self.ground_normal = normal;
+                remaining = CharacterController::project(
+                    &(horizontal + self.up.mul_n(vertical.max(0.0))),
+                    &normal,
+                );
+            } else if Vec2D::dot(&normal, &self.up) < -self.max_slope.get_radian().cos() {
+                self.ceiling = true;
+                remaining = CharacterController::project(&remaining, &normal);
+            } else {
+                // a ledge low enough is stepped on instead of stopping the character
+                if self.step_height > 0.0
+                    && (was_grounded || self.grounded)
+                    && self.step_up(map, horizontal)
+                {
+                    remaining = self.up.mul_n(vertical);
+                    continue;
+                }
+
+                // a steep slope can't be climbed by walking, only by jumping
+                self.wall = true;
+                remaining = CharacterController::project(&remaining, &normal);
+                if !jumping && Vec2D::dot(&remaining, &self.up) > 0.0 {
+                    remaining -= self.up.mul_n(Vec2D::dot(&remaining, &self.up));
+                }
+            }
+        }
+
+        // the ground may be right under the character even if it didn't hit it during the move
+        if !self.grounded && !jumping {
+            if let Some(normal) = self.sweep(map, self.up.mul_n(-self.skin * 2.0)).1 {
+                if self.is_walkable(&normal) {
+                    self.grounded = true;
+                    self.ground_normal = normal;
+                }
+            }
+        }
+
+        self.get_position() - start
+    }
+
+    /// Auxiliary function that raises the character by the step height, moves it forward and lowers it back
+    ///
+    /// The step is kept only if the character lands on the ground, otherwise it is returned to its place
+    fn step_up(&mut self, map: &Map, horizontal: Vec2D) -> bool {
+        let (raise, _) = self.sweep(map, self.up.mul_n(self.step_height));
+        self.translate(raise);
+        let (forward, _) = self.sweep(map, horizontal);
+        self.translate(forward);
+        let (lower, normal) = self.sweep(
+            map,
+            self.up
+                .mul_n(-(raise.len_vector(&Vec2D::default()) + self.skin)),
+        );
+        self.translate(lower);
+
+        match normal {
+            Some(normal)
+                if self.is_walkable(&normal)
+                    && forward.len_vector(&Vec2D::default()) > self.skin =>
+            {
+                self.grounded = true;
+                self.ground_normal = normal;
+                true
+            }
+            _ => {
+                self.translate((raise + forward + lower).mul_n(-1.0));
+                false
+            }
+        }
+    }
+
+    /// Auxiliary function that casts the shape by the displacement
+    ///
+    /// Returns the displacement to the first hit minus the skin, and the normal of the hit surface
+    fn sweep(&self, map: &Map, displacement: Vec2D) -> (Vec2D, Option<Vec2D>) {
+        let length = displacement.len_vector(&Vec2D::default());
+        if length == 0.0 {
+            return (Vec2D::default(), None);
+        }
+
+        let direction = displacement.unit();
+        match map.shape_cast(&self.shape.get_potential_vertex(), displacement) {
+            Some((fraction, _, normal, _)) => {
+                // the skin is kept along the normal, so the character stops further from surfaces hit at a sharp angle
+                let approach = (-Vec2D::dot(&direction, &normal)).max(f32::EPSILON);
+                let distance = (fraction * length - self.skin / approach).max(0.0);
+                (direction.mul_n(distance), Some(normal))
+            }
+            None => (displacement, None),
+        }
+    }
+
+    /// Auxiliary function that checks if the character can stand on the surface with the normal
+    fn is_walkable(&self, normal: &Vec2D) -> bool {
+        Vec2D::dot(normal, &self.up) >= self.max_slope.get_radian().cos()
+    }
+
+    /// Auxiliary function that removes the part of the vector going along the normal
+    fn project(vector: &Vec2D, normal: &Vec2D) -> Vec2D {
+        *vector - normal.mul_n(Vec2D::dot(vector, normal))
+    }
+
+    /// Auxiliary function that moves the shape with its vertices
+    fn translate(&mut self, displacement: Vec2D) {
+        let position = self.shape.get_current_position() + displacement;
+        let vertices = self
+            .shape
+            .get_potential_vertex()
+            .into_iter()
+            .map(|vertex| vertex + displacement)
+            .collect();
+        self.shape.set_current_position(position);
+        self.shape.set_potential_position(position);
+        self.shape.set_potential_vertex(vertices);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        objects::line::Line,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that creates a character of 20 x 20 standing on the floor of the map at the position
+    fn character(x: f32) -> CharacterController {
+        CharacterController::new(Box::new(square(Vec2D::new(x, 989.9), 20.0, 1.0)))
+    }
+
+    /// Auxiliary function that walks the character to the right in 20 moves
+    fn walk(character: &mut CharacterController, map: &Map, distance: f32) {
+        for _ in 0..20 {
+            character.move_by(map, Vec2D::new(distance / 20.0, 1.0));
+        }
+    }
+
+    /// Auxiliary function that creates a non-moving line
+    fn line(first_point: Vec2D, second_point: Vec2D) -> Line {
+        Line::new(first_point, second_point, 0.0, 0.0, Vec2D::default(), 0.5, 0.0, 0.0)
+    }
+
+    #[test]
+    fn character_reports_the_ground_and_the_ceiling() {
+        let map = map_with_gravity(Vec2D::default());
+        let mut character = character(500.0);
+
+        character.move_by(&map, Vec2D::new(0.0, 10.0));
+        assert!(character.is_grounded() && !character.is_on_wall() && !character.is_on_ceiling());
+        assert!((character.get_position().y - 989.9).abs() < 0.01);
+        assert!(character.get_ground_normal().y < -0.99);
+
+        // the top border stops the jump, the character keeps moving along it
+        character.set_position(Vec2D::new(500.0, 15.0));
+        character.move_by(&map, Vec2D::new(5.0, -20.0));
+        assert!(character.is_on_ceiling() && !character.is_grounded() && !character.is_on_wall());
+        assert!((character.get_position().y - 10.1).abs() < 0.01);
+        assert!((character.get_position().x - 505.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn character_slides_down_along_a_wall() {
+        let map = map_with_gravity(Vec2D::default());
+        let mut character = character(500.0);
+        character.set_position(Vec2D::new(985.0, 500.0));
+
+        let moved = character.move_by(&map, Vec2D::new(20.0, 20.0));
+        assert!(character.is_on_wall() && !character.is_grounded() && !character.is_on_ceiling());
+        assert!((character.get_position().x - 989.9).abs() < 0.01);
+        assert!((moved.y - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn character_walks_up_a_gentle_slope_but_not_a_steep_one() {
+        // the slopes begin at x = 600, the default max slope is 45 degrees
+        let mut positions = Vec::<Vec2D>::new();
+        for degrees in [30.0_f32, 60.0] {
+            let mut map = map_with_gravity(Vec2D::default());
+            map.add_object(Box::new(line(
+                Vec2D::new(600.0, 1000.0),
+                Vec2D::new(700.0, 1000.0 - 100.0 * degrees.to_radians().tan()),
+            )));
+            let mut character = character(550.0);
+            walk(&mut character, &map, 100.0);
+            positions.push(character.get_position());
+
+            if degrees < 45.0 {
+                assert!(character.is_grounded() && !character.is_on_wall());
+                assert!(character.get_position().x > 600.0);
+            } else {
+                assert!(character.is_on_wall());
+                assert!(character.get_position().x < 600.0);
+            }
+        }
+
+        // the right bottom corner of the character stands on the gentle slope, the steep one is not climbed at all
+        let height = (positions[0].x + 10.0 - 600.0) * 30.0_f32.to_radians().tan();
+        assert!((989.9 - positions[0].y - height).abs() < 0.5, "{:?}", positions[0]);
+        assert!((positions[1].y - 989.9).abs() < 0.01, "{:?}", positions[1]);
+    }
+
+    #[test]
+    fn character_steps_up_only_a_low_ledge() {
+        for height in [5.0, 15.0] {
+            let mut map = map_with_gravity(Vec2D::default());
+            map.add_object(Box::new(line(Vec2D::new(600.0, 1000.0), Vec2D::new(600.0, 1000.0 - height))));
+            map.add_object(Box::new(line(
+                Vec2D::new(600.0, 1000.0 - height),
+                Vec2D::new(800.0, 1000.0 - height),
+            )));
+            let mut character = character(550.0);
+            character.set_step_height(8.0);
+            walk(&mut character, &map, 100.0);
+
+            if height < 8.0 {
+                assert!(character.is_grounded() && !character.is_on_wall());
+                assert!((character.get_position().y - (989.9 - height)).abs() < 0.01);
+                assert!(character.get_position().x > 600.0);
+            } else {
+                assert!(character.is_grounded() && character.is_on_wall());
+                assert!((character.get_position().y - 989.9).abs() < 0.01);
+                assert!((character.get_position().x - 589.9).abs() < 0.01);
+            }
+        }
+    }
+}
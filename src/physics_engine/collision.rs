@@ -1,114 +1,477 @@
-use super::{traits::object_interface::ObjectInterface, types::vec2d::Vec2D};
+use super::{
+    query::contact_points,
+    solver::{ObjectId, Solver, SolverBody},
+    traits::{
+        constraint_interface::ConstraintInterface, move_interface::MoveInterface,
+        object_interface::ObjectInterface,
+    },
+    types::{material::Material, vec2d::Vec2D},
+};
 
-/// A structure that solves the collision of two objects
-pub struct Collision<'a> {
-    object1: &'a mut dyn ObjectInterface,
-    object2: &'a mut dyn ObjectInterface,
+/// Distance within which the contact vertices of two steps are considered the same contact
+const WARM_START_DISTANCE: f32 = 2.0;
+
+/// Information about the contact of two objects during a run of the map
+#[derive(Debug, Copy, Clone)]
+pub struct ContactEvent {
+    pub object1: ObjectId,
+    pub object2: ObjectId,
+    /// Contact vertex of the last substep
+    pub contact_vertex: Vec2D,
+    /// Axis of the last substep along which the first object is pushed
+    pub smallest_axis: Vec2D,
+    /// Sum of the normal impulses of all contact vertices and substeps
+    pub impulse: f32,
+}
+
+/// A structure describing the contact of two objects, which is resolved by the solver
+pub struct Collision {
+    object1: ObjectId,
+    object2: ObjectId,
     min_overlap: f32,
     smallest_axis: Vec2D,
     contact_vertex: Vec2D,
+    collision_arm1: Vec2D,
+    collision_arm2: Vec2D,
+    elasticity: f32,
+    material: Material,
+    surface_velocity: f32,
+    normal_mass: f32,
+    tangent_mass: f32,
+    separate_velocity: f32,
+    new_separate_velocity: f32,
+    impulse: f32,
+    friction_impulse: f32,
+    start_position1: Vec2D,
+    start_angle1: f32,
+    start_position2: Vec2D,
+    start_angle2: f32,
+    time: f32,
 }
 
-impl<'a> Collision<'a> {
+impl Collision {
     /// Creating a structure with information about a collision
+    ///
+    /// `smallest_axis` is the unit axis along which the first object has to be pushed to separate the objects
     pub fn new(
-        object1: &'a mut dyn ObjectInterface,
-        object2: &'a mut dyn ObjectInterface,
+        object1: (ObjectId, &dyn ObjectInterface),
+        object2: (ObjectId, &dyn ObjectInterface),
         min_overlap: f32,
         smallest_axis: Vec2D,
         contact_vertex: Vec2D,
     ) -> Self {
+        // moving surfaces of the objects are taken into account by the friction along the contact surface
+        let surface_velocity = object1.1.get_direction().mul_n(object1.1.get_surface_velocity())
+            - object2.1.get_direction().mul_n(object2.1.get_surface_velocity());
+
         Collision {
-            object1,
-            object2,
+            object1: object1.0,
+            object2: object2.0,
             min_overlap,
             smallest_axis,
             contact_vertex,
+            collision_arm1: contact_vertex - object1.1.get_potential_position(),
+            collision_arm2: contact_vertex - object2.1.get_potential_position(),
+            elasticity: object1.1.get_elasticity().min(object2.1.get_elasticity()),
+            material: object1.1.get_material().combine(&object2.1.get_material()),
+            surface_velocity: Vec2D::dot(&surface_velocity, &smallest_axis.normal()),
+            normal_mass: 0.0,
+            tangent_mass: 0.0,
+            separate_velocity: 0.0,
+            new_separate_velocity: 0.0,
+            impulse: 0.0,
+            friction_impulse: 0.0,
+            start_position1: Vec2D::default(),
+            start_angle1: 0.0,
+            start_position2: Vec2D::default(),
+            start_angle2: 0.0,
+            time: 0.0,
         }
     }
 
-    /// Modifies object properties to separate objects by smallest asix
-    pub fn divide_objects(&mut self) {
-        // separates 2 objects along the smallest axis
-        let divide_objects = self.smallest_axis.mul_n(
-            self.min_overlap
-                / (self.object1.get_inversion_mass() + self.object2.get_inversion_mass()),
-        );
+    /// Checks two objects for collision and creates a collision for every contact vertex
+    pub fn detect(
+        object1: (ObjectId, &dyn MoveInterface),
+        object2: (ObjectId, &dyn ObjectInterface),
+    ) -> Vec<Collision> {
+        // checks circumscribed circles for collision
+        if !object1.1.intersection_circumscribed_circles(object2.1) {
+            return Vec::new();
+        }
 
-        // pushes 1 object away
-        self.object1.set_current_position(
-            self.object1.get_current_position()
-                + divide_objects.mul_n(self.object1.get_inversion_mass()),
-        );
-        // pushes 2 object away
-        self.object2.set_current_position(
-            self.object2.get_current_position()
-                + divide_objects.mul_n(-self.object2.get_inversion_mass()),
+        // checks for collision using the sat method
+        let (min_overlap, smallest_axis, contact_vertex) = match object1.1.sat(object2.1) {
+            Some(mtv) => mtv,
+            None => return Vec::new(),
+        };
+
+        // two contact vertices keep resting objects from rocking around a single one
+        let mut contacts = contact_points(
+            &object1.1.get_potential_vertex(),
+            &object2.1.get_potential_vertex(),
+            &smallest_axis,
         );
+        if contacts.is_empty() {
+            contacts.push((contact_vertex, min_overlap));
+        }
+
+        contacts
+            .into_iter()
+            .map(|(contact_vertex, overlap)| {
+                Collision::new(
+                    (object1.0, object1.1.as_object()),
+                    object2,
+                    overlap,
+                    smallest_axis,
+                    contact_vertex,
+                )
+            })
+            .collect()
     }
 
-    /// Simulates the collision of two objects , changing their properties
-    pub fn change_energy(&mut self) {
-        // calculates directions after collision, speeds after separation
-        let collision_arm1 = self.contact_vertex - self.object1.get_potential_position();
-        let rotation_velocity1 = Vec2D::new(
-            -self.object1.get_angle_velocity() * collision_arm1.y,
-            self.object1.get_angle_velocity() * collision_arm1.x,
-        );
-        let closing_velocity1 = self.object1.get_velocity() + rotation_velocity1;
-        let collision_arm2 = self.contact_vertex - self.object2.get_potential_position();
-        let rotation_velocity2 = Vec2D::new(
-            -self.object2.get_angle_velocity() * collision_arm2.y,
-            self.object2.get_angle_velocity() * collision_arm2.x,
-        );
-        let closing_velocity2 = self.object2.get_velocity() + rotation_velocity2;
-        
-        // calculates the momentum acceleration
-        let mut impulse_augmentation1 = Vec2D::cross(&collision_arm1, &self.smallest_axis);
-        impulse_augmentation1 =
-            impulse_augmentation1 * self.object1.get_inversion_inertia() * impulse_augmentation1;
-        let mut impulse_augmentation2 = Vec2D::cross(&collision_arm2, &self.smallest_axis);
-        impulse_augmentation2 =
-            impulse_augmentation2 * self.object2.get_inversion_inertia() * impulse_augmentation2;
-
-        // calculates velocity after collision
-        let relative_velocity = closing_velocity1 - closing_velocity2;
-        let separate_velocity = Vec2D::dot(&relative_velocity, &self.smallest_axis);
-        let new_separate_velocity = -separate_velocity
-            * self
-                .object1
-                .get_elasticity()
-                .min(self.object2.get_elasticity());
-        let separate_velocity_difference = new_separate_velocity - separate_velocity;
-
-        // calculates the momentum to further change characteristics
-        let impulse = separate_velocity_difference
-            / (self.object1.get_inversion_mass()
-                + self.object2.get_inversion_mass()
-                + impulse_augmentation1
-                + impulse_augmentation2);
-        let impulse_vector = self.smallest_axis.mul_n(impulse);
-
-        // changes the velocity of 1 object
-        self.object1.set_velocity(
-            self.object1.get_velocity() + impulse_vector.mul_n(self.object1.get_inversion_mass()),
-        );
-        // changes the velocity of 2 object
-        self.object2.set_velocity(
-            self.object2.get_velocity() + impulse_vector.mul_n(-self.object2.get_inversion_mass()),
-        );
+    /// Continues the impulses of the same contact found in the previous step, so that resting contacts converge faster
+    pub fn warm_start(&mut self, collision: &Collision) -> bool {
+        if (self.object1, self.object2) != (collision.object1, collision.object2)
+            || Vec2D::dot(&self.smallest_axis, &collision.smallest_axis) < 0.9
+            || self.contact_vertex.len_vector(&collision.contact_vertex) > WARM_START_DISTANCE
+        {
+            return false;
+        }
 
-        // changes the angular velocity of 1 object
-        self.object1.set_angle_velocity(
-            self.object1.get_angle_velocity()
-                + self.object1.get_inversion_inertia()
-                    * Vec2D::cross(&collision_arm1, &impulse_vector),
-        );
-        // changes the angular velocity of 2 object
-        self.object2.set_angle_velocity(
-            self.object2.get_angle_velocity()
-                - self.object2.get_inversion_inertia()
-                    * Vec2D::cross(&collision_arm2, &impulse_vector),
-        );
+        self.impulse = collision.impulse;
+        self.friction_impulse = collision.friction_impulse;
+
+        true
+    }
+
+    /// Adds the collision to the event of the same objects or creates a new event
+    pub fn to_event(&self, event: Option<ContactEvent>) -> ContactEvent {
+        ContactEvent {
+            object1: self.object1,
+            object2: self.object2,
+            contact_vertex: self.contact_vertex,
+            smallest_axis: self.smallest_axis,
+            impulse: event.map_or(0.0, |event| event.impulse) + self.impulse,
+        }
+    }
+
+    pub fn get_min_overlap(&self) -> f32 {
+        self.min_overlap
+    }
+
+    pub fn get_smallest_axis(&self) -> Vec2D {
+        self.smallest_axis
+    }
+
+    pub fn get_contact_vertex(&self) -> Vec2D {
+        self.contact_vertex
+    }
+
+    /// Returns the accumulated normal impulse of the collision
+    pub fn get_impulse(&self) -> f32 {
+        self.impulse
+    }
+
+    /// Returns the accumulated friction impulse of the collision, directed along the normal of the smallest axis
+    pub fn get_friction_impulse(&self) -> f32 {
+        self.friction_impulse
+    }
+
+    /// Auxiliary function for calculating the inverted mass of two bodies pushed along the axis at the contact vertex
+    fn get_effective_mass(&self, body1: &SolverBody, body2: &SolverBody, axis: &Vec2D) -> f32 {
+        let impulse_augmentation1 = Vec2D::cross(&self.collision_arm1, axis);
+        let impulse_augmentation2 = Vec2D::cross(&self.collision_arm2, axis);
+        let inversion_mass = body1.inversion_mass
+            + body2.inversion_mass
+            + impulse_augmentation1 * body1.inversion_inertia * impulse_augmentation1
+            + impulse_augmentation2 * body2.inversion_inertia * impulse_augmentation2;
+
+        if inversion_mass == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_mass
+        }
+    }
+
+    /// Auxiliary function for calculating the velocity with which the objects move apart at the contact vertex
+    fn get_separate_velocity(&self, body1: &SolverBody, body2: &SolverBody) -> f32 {
+        let relative_velocity =
+            body1.velocity_at(&self.collision_arm1) - body2.velocity_at(&self.collision_arm2);
+
+        Vec2D::dot(&relative_velocity, &self.smallest_axis)
+    }
+}
+
+impl ConstraintInterface for Collision {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        // calculates the momentum acceleration along the axis and along the contact surface
+        self.normal_mass = self.get_effective_mass(body1, body2, &self.smallest_axis);
+        self.tangent_mass = self.get_effective_mass(body1, body2, &self.smallest_axis.normal());
+
+        // calculates the velocity after collision, only approaching objects bounce off
+        self.separate_velocity = self.get_separate_velocity(body1, body2);
+        self.new_separate_velocity = if self.separate_velocity < 0.0 {
+            -self.separate_velocity * self.elasticity
+        } else {
+            0.0
+        };
+
+        // applies the impulses continued from the previous step
+        let impulse_vector = self.smallest_axis.mul_n(self.impulse)
+            + self.smallest_axis.normal().mul_n(self.friction_impulse);
+        body1.apply_impulse(&impulse_vector, &self.collision_arm1);
+        body2.apply_impulse(&impulse_vector.mul_n(-1.0), &self.collision_arm2);
+
+        self.start_position1 = body1.position;
+        self.start_angle1 = body1.angle;
+        self.start_position2 = body2.position;
+        self.start_angle2 = body2.angle;
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        // friction goes first, because its limit depends on the normal impulse
+        let tangent = self.smallest_axis.normal();
+        let relative_velocity =
+            body1.velocity_at(&self.collision_arm1) - body2.velocity_at(&self.collision_arm2);
+        let friction_impulse = self.friction_impulse
+            - self.tangent_mass * (Vec2D::dot(&relative_velocity, &tangent) + self.surface_velocity);
+
+        // objects stick while the impulse fits into the static friction cone, otherwise they slide with dynamic friction
+        let new_friction_impulse =
+            if friction_impulse.abs() <= self.material.get_static_friction() * self.impulse {
+                friction_impulse
+            } else {
+                let max_friction = self.material.get_dynamic_friction() * self.impulse;
+                friction_impulse.clamp(-max_friction, max_friction)
+            };
+        let friction_vector = tangent.mul_n(new_friction_impulse - self.friction_impulse);
+        self.friction_impulse = new_friction_impulse;
+
+        body1.apply_impulse(&friction_vector, &self.collision_arm1);
+        body2.apply_impulse(&friction_vector.mul_n(-1.0), &self.collision_arm2);
+
+        let separate_velocity = self.get_separate_velocity(body1, body2);
+
+        // the accumulated impulse can only push objects apart, so the applied part is clamped
+        let impulse = self.normal_mass * (self.new_separate_velocity - separate_velocity);
+        let new_impulse = (self.impulse + impulse).max(0.0);
+        let impulse_vector = self.smallest_axis.mul_n(new_impulse - self.impulse);
+        self.impulse = new_impulse;
+
+        body1.apply_impulse(&impulse_vector, &self.collision_arm1);
+        body2.apply_impulse(&impulse_vector.mul_n(-1.0), &self.collision_arm2);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // the overlap was found for the old velocities, so it is reduced by the changes of the velocities and positions
+        let displacement1 = body1.position - self.start_position1
+            + self.collision_arm1.normal().mul_n(body1.angle - self.start_angle1);
+        let displacement2 = body2.position - self.start_position2
+            + self.collision_arm2.normal().mul_n(body2.angle - self.start_angle2);
+        let overlap = self.min_overlap
+            - (self.get_separate_velocity(body1, body2) - self.separate_velocity) * self.time
+            - Vec2D::dot(&(displacement1 - displacement2), &self.smallest_axis);
+
+        // a small overlap is kept so that resting contacts persist, the rest is removed gradually
+        let correction = solver.get_correction(overlap);
+        if correction <= 0.0 {
+            return;
+        }
+
+        // separates 2 objects along the smallest axis
+        let divide_objects = self.smallest_axis.mul_n(correction * self.normal_mass);
+        body1.apply_displacement(&divide_objects, &self.collision_arm1);
+        body2.apply_displacement(&divide_objects.mul_n(-1.0), &self.collision_arm2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        map::Map,
+        objects::{line::Line, rectangle::Rectangle},
+    };
+
+    const TIME: f32 = 1.0 / 60.0;
+
+    /// Auxiliary function that creates a map with a stack of boxes of the mass lying on the floor
+    fn resting_boxes(count: usize, mass: f32) -> Map {
+        let mut map = Map::new();
+        map.set_gravity(Vec2D::new(0.0, 100.0));
+        map.set_time_to_sleep(f32::INFINITY);
+        map.init_map_border(Vec2D::new(0.0, 0.0), Vec2D::new(1000.0, 1000.0));
+        for n in 0..count {
+            let y = 1000.0 - 20.0 * n as f32;
+            map.dyn_objects.push(Box::new(Rectangle::new(
+                Vec2D::new(480.0, y),
+                Vec2D::new(520.0, y),
+                20.0,
+                mass,
+                0.0,
+                Vec2D::default(),
+                0.5,
+                0.0,
+                0.0,
+            )));
+        }
+
+        map
+    }
+
+    #[test]
+    fn resting_contact_stays_at_rest() {
+        let mut map = resting_boxes(1, 1.0);
+        for _ in 0..60 {
+            map.run(TIME);
+        }
+
+        let position = map.dyn_objects[0].get_current_position();
+        let angle = map.dyn_objects[0].get_angle().get_signed_radian();
+        for _ in 0..300 {
+            map.run(TIME);
+        }
+
+        let dyn_object = &map.dyn_objects[0];
+        assert!(dyn_object.get_current_position().len_vector(&position) < 0.01);
+        assert!((dyn_object.get_angle().get_signed_radian() - angle).abs() < 0.001);
+        assert!(dyn_object.get_velocity().len_vector(&Vec2D::default()) < 0.1);
+        assert!(dyn_object.get_angle_velocity().abs() < 0.01);
+    }
+
+    #[test]
+    fn warm_starting_holds_a_stack_with_few_iterations() {
+        // two iterations can't pass the weight of the stack down in one step, so the impulses have to be carried over the steps
+        let mut map = resting_boxes(5, 1.0);
+        map.get_mut_solver().set_velocity_iterations(2);
+        let top = map.dyn_objects[4].get_current_position();
+        for _ in 0..240 {
+            map.run(TIME);
+        }
+
+        let weight_impulse = 5.0 * 100.0 * TIME;
+        let impulse: f32 = map
+            .get_contact_events()
+            .iter()
+            .filter(|event| matches!(event.object2, ObjectId::Static(_)))
+            .map(|event| event.impulse)
+            .sum();
+        assert!((impulse - weight_impulse).abs() < weight_impulse * 0.01);
+        assert!(map.dyn_objects[4].get_current_position().len_vector(&top) < 1.0);
+    }
+
+    #[test]
+    fn friction_of_the_objects_stops_a_sliding_box() {
+        let mut map = resting_boxes(2, 1.0);
+        map.dyn_objects[1].set_current_position(Vec2D::new(100.0, 990.0));
+        map.dyn_objects[1].set_friction(0.0);
+        for dyn_object in map.dyn_objects.iter_mut() {
+            dyn_object.set_velocity(Vec2D::new(50.0, 0.0));
+        }
+        for _ in 0..120 {
+            map.run(TIME);
+        }
+
+        // the friction of the box is combined with the one of the floor, a frictionless box keeps sliding
+        assert!(map.dyn_objects[0].get_velocity().x.abs() < 0.1);
+        assert!((map.dyn_objects[1].get_velocity().x - 50.0).abs() < 0.1);
+        assert!((map.dyn_objects[1].get_current_position().y - 990.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn warm_start_continues_only_the_same_contact() {
+        let map = resting_boxes(1, 1.0);
+        let dyn_object = map.dyn_objects[0].as_object();
+        let floor = Rectangle::default();
+        let new = |axis: Vec2D, vertex: Vec2D| {
+            Collision::new(
+                (ObjectId::Dynamic(0), dyn_object),
+                (ObjectId::Static(2), &floor),
+                0.1,
+                axis,
+                vertex,
+            )
+        };
+
+        let mut previous = new(Vec2D::new(0.0, -1.0), Vec2D::new(480.0, 1000.0));
+        previous.impulse = 3.0;
+        previous.friction_impulse = 0.5;
+
+        let mut same = new(Vec2D::new(0.0, -1.0), Vec2D::new(480.5, 1000.0));
+        assert!(same.warm_start(&previous));
+        assert_eq!((same.get_impulse(), same.get_friction_impulse()), (3.0, 0.5));
+
+        let mut turned = new(Vec2D::new(1.0, 0.0), Vec2D::new(480.0, 1000.0));
+        assert!(!turned.warm_start(&previous));
+        assert_eq!(turned.get_impulse(), 0.0);
+
+        let mut moved = new(Vec2D::new(0.0, -1.0), Vec2D::new(520.0, 1000.0));
+        assert!(!moved.warm_start(&previous));
+    }
+
+    #[test]
+    fn overlap_is_removed_gradually_down_to_the_slop() {
+        for slop in [0.5, 0.1] {
+            let mut map = resting_boxes(1, 1.0);
+            map.get_mut_solver().set_slop(slop);
+            map.dyn_objects[0].set_current_position(Vec2D::new(500.0, 995.0));
+
+            // the box sunk into the floor by 5 is pushed out over several steps and doesn't jump out of it
+            for n in 0..60 {
+                map.run(TIME);
+                let dyn_object = &map.dyn_objects[0];
+                assert!(dyn_object.get_velocity().len_vector(&Vec2D::default()) < 0.01, "{}", n);
+                if n == 0 {
+                    assert!(dyn_object.get_current_position().y > 992.0);
+                }
+            }
+
+            let overlap = map.dyn_objects[0].get_current_position().y - 990.0;
+            assert!((overlap - slop).abs() < 0.05, "{} {}", slop, overlap);
+        }
+    }
+
+    #[test]
+    fn conveyor_carries_the_resting_box() {
+        for surface_velocity in [50.0, -50.0] {
+            let mut map = resting_boxes(0, 1.0);
+            let mut conveyor = Line::new(
+                Vec2D::new(300.0, 800.0),
+                Vec2D::new(700.0, 800.0),
+                0.0,
+                0.0,
+                Vec2D::default(),
+                0.5,
+                0.0,
+                0.0,
+            );
+            conveyor.set_surface_velocity(surface_velocity);
+            map.add_object(Box::new(conveyor));
+            map.dyn_objects.push(Box::new(Rectangle::new(
+                Vec2D::new(490.0, 800.0),
+                Vec2D::new(510.0, 800.0),
+                20.0,
+                1.0,
+                0.0,
+                Vec2D::default(),
+                0.5,
+                0.0,
+                0.0,
+            )));
+            for _ in 0..60 {
+                map.run(TIME);
+            }
+
+            // the friction speeds the box up to the velocity of the surface along the direction of the line
+            let dyn_object = &map.dyn_objects[0];
+            assert!((dyn_object.get_velocity().x - surface_velocity).abs() < 0.01, "{:?}", dyn_object.get_velocity());
+            assert!((dyn_object.get_current_position().y - 790.0).abs() < 0.01);
+            assert!(dyn_object.get_angle_velocity().abs() < 0.01);
+        }
     }
 }
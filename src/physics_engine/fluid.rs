@@ -0,0 +1,195 @@
+use super::{query::signed_area, traits::move_interface::MoveInterface, types::vec2d::Vec2D};
+
+/// Area of the map filled with a fluid, e.g. a puddle, that pushes up and slows down the objects in it
+///
+/// The buoyancy is the weight of the displaced fluid, so objects whose mass divided by their area is less than
+/// the density of the fluid float and the others sink
+pub struct FluidRegion {
+    vertices: Vec<Vec2D>,
+    density: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+}
+
+impl FluidRegion {
+    /// Creating a fluid region in the form of a polygon, the vertices go around its perimeter
+    ///
+    /// Drags are damping per second of an object with the density of the fluid, denser objects slow down less
+    pub fn new(vertices: Vec<Vec2D>, density: f32, linear_drag: f32, angular_drag: f32) -> Self {
+        FluidRegion {
+            vertices,
+            density,
+            linear_drag,
+            angular_drag,
+        }
+    }
+
+    /// Creating a fluid region in the form of an axis-aligned rectangle given by the left top and the right bottom points
+    pub fn from_aabb(plt: Vec2D, prb: Vec2D, density: f32, linear_drag: f32, angular_drag: f32) -> Self {
+        FluidRegion::new(
+            vec![plt, Vec2D::new(prb.x, plt.y), prb, Vec2D::new(plt.x, prb.y)],
+            density,
+            linear_drag,
+            angular_drag,
+        )
+    }
+
+    pub fn set_vertices(&mut self, vertices: Vec<Vec2D>) {
+        self.vertices = vertices;
+    }
+
+    pub fn get_vertices(&self) -> Vec<Vec2D> {
+        self.vertices.clone()
+    }
+
+    pub fn set_density(&mut self, density: f32) {
+        self.density = density;
+    }
+
+    pub fn get_density(&self) -> f32 {
+        self.density
+    }
+
+    pub fn set_linear_drag(&mut self, linear_drag: f32) {
+        self.linear_drag = linear_drag;
+    }
+
+    pub fn get_linear_drag(&self) -> f32 {
+        self.linear_drag
+    }
+
+    pub fn set_angular_drag(&mut self, angular_drag: f32) {
+        self.angular_drag = angular_drag;
+    }
+
+    pub fn get_angular_drag(&self) -> f32 {
+        self.angular_drag
+    }
+
+    /// Calculates the area and the centroid of the part of the polygon under the fluid
+    ///
+    /// The polygon must be convex, the fluid region may be of any form. Returns `None` if the polygon is not in the fluid
+    pub fn get_submerged(&self, polygon: &[Vec2D]) -> Option<(f32, Vec2D)> {
+        if polygon.len() < 3 || self.vertices.len() < 3 {
+            return None;
+        }
+
+        // the fluid is clipped by every edge of the polygon, so that only its part inside the polygon remains
+        let orientation = signed_area(polygon).signum();
+        let mut submerged = self.vertices.clone();
+        for n in 0..polygon.len() {
+            let start = polygon[n];
+            let edge = polygon[(n + 1) % polygon.len()] - start;
+            let inside = |point: &Vec2D| Vec2D::cross(&edge, &(*point - start)) * orientation >= 0.0;
+
+            let mut clipped = Vec::<Vec2D>::new();
+            for k in 0..submerged.len() {
+                let current = submerged[k];
+                let next = submerged[(k + 1) % submerged.len()];
+                if inside(&current) {
+                    clipped.push(current);
+                }
+
+                // adds the point where the side of the fluid crosses the edge
+                if inside(&current) != inside(&next) {
+                    let side = next - current;
+                    let t = Vec2D::cross(&edge, &(start - current)) / Vec2D::cross(&edge, &side);
+                    clipped.push(current + side.mul_n(t));
+                }
+            }
+
+            submerged = clipped;
+            if submerged.len() < 3 {
+                return None;
+            }
+        }
+
+        let area = signed_area(&submerged);
+        if area.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        // the centroid is the mean of the centroids of the triangles from the origin weighted by their signed areas
+        let centroid = (0..submerged.len())
+            .fold(Vec2D::default(), |centroid, n| {
+                let current = submerged[n];
+                let next = submerged[(n + 1) % submerged.len()];
+                centroid + (current + next).mul_n(Vec2D::cross(&current, &next))
+            })
+            .mul_n(1.0 / (6.0 * area));
+
+        Some((area.abs(), centroid))
+    }
+
+    /// Calculates the buoyancy and the drag of the object in the fluid under the gravity
+    ///
+    /// Returns the force, the point of the map to which it is applied and the torque of the drag, or `None` if the object is not in the fluid
+    pub fn get_force(&self, object: &dyn MoveInterface, gravity: Vec2D) -> Option<(Vec2D, Vec2D, f32)> {
+        let (area, centroid) = self.get_submerged(&object.get_potential_vertex())?;
+        let displaced_mass = self.density * area;
+
+        // the fluid resists the movement of the submerged part
+        let arm = centroid - object.get_current_position();
+        let velocity = object.get_velocity() + arm.normal().mul_n(object.get_angle_velocity());
+        let buoyancy = gravity.mul_n(-displaced_mass);
+        let drag = velocity.mul_n(-self.linear_drag * displaced_mass);
+
+        let torque = if object.get_mass() == 0.0 {
+            0.0
+        } else {
+            -self.angular_drag * displaced_mass * object.get_inertia() / object.get_mass()
+                * object.get_angle_velocity()
+        };
+
+        Some((buoyancy + drag, centroid, torque))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        map::Map,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that drops boxes of the masses into a pool of the density 0.01 filling the lower half of the map
+    fn pool(masses: &[f32]) -> Map {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.fluids.push(FluidRegion::from_aabb(
+            Vec2D::new(0.0, 500.0),
+            Vec2D::new(1000.0, 1000.0),
+            0.01,
+            2.0,
+            2.0,
+        ));
+        for (n, mass) in masses.iter().enumerate() {
+            map.dyn_objects
+                .push(Box::new(square(Vec2D::new(200.0 + 100.0 * n as f32, 450.0), 20.0, *mass)));
+        }
+        for _ in 0..600 {
+            map.run(1.0 / 60.0);
+        }
+
+        map
+    }
+
+    #[test]
+    fn light_boxes_float_at_their_draft() {
+        let map = pool(&[1.0, 2.0, 3.0]);
+
+        // the displaced fluid weighs as much as the box up to the slow sinking before sleep, so the draft is the mass divided by the density and the width
+        for (dyn_object, mass) in map.dyn_objects.iter().zip([1.0, 2.0, 3.0]) {
+            let draft = mass / (0.01 * 20.0);
+            let bottom = dyn_object.get_current_position().y + 10.0;
+            assert!((bottom - 500.0 - draft).abs() < 1.0, "{}", mass);
+        }
+    }
+
+    #[test]
+    fn heavy_box_sinks() {
+        let map = pool(&[8.0]);
+
+        assert!((map.dyn_objects[0].get_current_position().y - 990.0).abs() < 0.5);
+    }
+}
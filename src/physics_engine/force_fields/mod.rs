@@ -0,0 +1,5 @@
+pub mod uniform;
+
+pub mod radial;
+
+pub mod vortex;
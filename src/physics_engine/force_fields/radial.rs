@@ -0,0 +1,83 @@
+use super::super::{
+    traits::force_field_interface::ForceFieldInterface,
+    types::{region::Region, vec2d::Vec2D},
+};
+
+/// Field that pulls objects to its center or pushes them away from it
+pub struct RadialField {
+    center: Vec2D,
+    strength: f32,
+    region: Option<Region>,
+}
+
+impl RadialField {
+    /// Creating a radial field, a positive strength attracts objects and a negative one repels them
+    pub fn new(center: Vec2D, strength: f32, region: Option<Region>) -> Self {
+        RadialField {
+            center,
+            strength,
+            region,
+        }
+    }
+
+    pub fn set_center(&mut self, center: Vec2D) {
+        self.center = center;
+    }
+
+    pub fn get_center(&self) -> Vec2D {
+        self.center
+    }
+
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength;
+    }
+
+    pub fn get_strength(&self) -> f32 {
+        self.strength
+    }
+
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+}
+
+impl ForceFieldInterface for RadialField {
+    fn get_acceleration_at(&self, position: &Vec2D) -> Vec2D {
+        match self.region {
+            Some(region) if !region.contains(position) => Vec2D::default(),
+            _ => (self.center - *position).unit().mul_n(self.strength),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::{map_with_gravity, square};
+
+    #[test]
+    fn radial_field_attracts_and_repels_the_objects() {
+        for strength in [100.0, -100.0] {
+            let mut map = map_with_gravity(Vec2D::default());
+            map.force_fields
+                .push(Box::new(RadialField::new(Vec2D::new(500.0, 500.0), strength, None)));
+            map.dyn_objects
+                .push(Box::new(square(Vec2D::new(300.0, 500.0), 20.0, 1.0)));
+            map.dyn_objects
+                .push(Box::new(square(Vec2D::new(500.0, 700.0), 20.0, 5.0)));
+            for _ in 0..30 {
+                map.run(1.0 / 60.0);
+            }
+
+            // the objects move along the line to the center, a positive strength pulls them to it
+            let velocity1 = map.dyn_objects[0].get_velocity();
+            let velocity2 = map.dyn_objects[1].get_velocity();
+            assert!((velocity1.x - strength / 2.0).abs() < 0.01 && velocity1.y.abs() < 0.01, "{:?}", velocity1);
+            assert!((velocity2.y + strength / 2.0).abs() < 0.01 && velocity2.x.abs() < 0.01, "{:?}", velocity2);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use super::super::{
+    traits::force_field_interface::ForceFieldInterface,
+    types::{region::Region, vec2d::Vec2D},
+};
+
+/// Field with the same acceleration everywhere in its region
+pub struct UniformField {
+    acceleration: Vec2D,
+    region: Option<Region>,
+}
+
+impl UniformField {
+    /// Creating a uniform field, without a region it acts on the whole map
+    pub fn new(acceleration: Vec2D, region: Option<Region>) -> Self {
+        UniformField {
+            acceleration,
+            region,
+        }
+    }
+
+    pub fn set_acceleration(&mut self, acceleration: Vec2D) {
+        self.acceleration = acceleration;
+    }
+
+    pub fn get_acceleration(&self) -> Vec2D {
+        self.acceleration
+    }
+
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+}
+
+impl ForceFieldInterface for UniformField {
+    fn get_acceleration_at(&self, position: &Vec2D) -> Vec2D {
+        match self.region {
+            Some(region) if !region.contains(position) => Vec2D::default(),
+            _ => self.acceleration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::{map_with_gravity, square};
+
+    #[test]
+    fn uniform_field_accelerates_only_the_objects_in_its_region() {
+        let mut map = map_with_gravity(Vec2D::default());
+        map.force_fields.push(Box::new(UniformField::new(
+            Vec2D::new(0.0, 100.0),
+            Some(Region::Aabb(Vec2D::new(500.0, 0.0), Vec2D::new(1000.0, 1000.0))),
+        )));
+        for (x, mass) in [(600.0, 1.0), (800.0, 10.0), (200.0, 1.0)] {
+            map.dyn_objects.push(Box::new(square(Vec2D::new(x, 100.0), 20.0, mass)));
+        }
+        for _ in 0..60 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the force is scaled by the mass, so the light and the heavy boxes fall together
+        assert!((map.dyn_objects[0].get_velocity().y - 100.0).abs() < 0.01);
+        assert!((map.dyn_objects[1].get_velocity().y - 100.0).abs() < 0.01);
+        assert!(map.dyn_objects[2].get_velocity().len_vector(&Vec2D::default()) < 0.01);
+    }
+}
@@ -0,0 +1,55 @@
+use super::super::{
+    traits::force_field_interface::ForceFieldInterface,
+    types::{region::Region, vec2d::Vec2D},
+};
+
+/// Field that spins objects around its center
+pub struct VortexField {
+    center: Vec2D,
+    strength: f32,
+    region: Option<Region>,
+}
+
+impl VortexField {
+    /// Creating a vortex field, the sign of the strength gives the direction of rotation
+    pub fn new(center: Vec2D, strength: f32, region: Option<Region>) -> Self {
+        VortexField {
+            center,
+            strength,
+            region,
+        }
+    }
+
+    pub fn set_center(&mut self, center: Vec2D) {
+        self.center = center;
+    }
+
+    pub fn get_center(&self) -> Vec2D {
+        self.center
+    }
+
+    pub fn set_strength(&mut self, strength: f32) {
+        self.strength = strength;
+    }
+
+    pub fn get_strength(&self) -> f32 {
+        self.strength
+    }
+
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+}
+
+impl ForceFieldInterface for VortexField {
+    fn get_acceleration_at(&self, position: &Vec2D) -> Vec2D {
+        match self.region {
+            Some(region) if !region.contains(position) => Vec2D::default(),
+            _ => (*position - self.center).unit().normal().mul_n(self.strength),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use super::types::vec2d::Vec2D;
+
+/// Position and velocity of an object during the integration
+#[derive(Debug, Copy, Clone)]
+pub struct Motion {
+    pub position: Vec2D,
+    pub angle: f32,
+    pub velocity: Vec2D,
+    pub angle_velocity: f32,
+}
+
+impl Motion {
+    /// Creating a motion
+    pub fn new(position: Vec2D, angle: f32, velocity: Vec2D, angle_velocity: f32) -> Self {
+        Motion {
+            position,
+            angle,
+            velocity,
+            angle_velocity,
+        }
+    }
+
+    /// Auxiliary function that moves the motion by the derivatives over the time
+    fn step(&self, derivative: &Derivative, time: f32) -> Motion {
+        Motion::new(
+            self.position + derivative.velocity.mul_n(time),
+            self.angle + derivative.angle_velocity * time,
+            self.velocity + derivative.acceleration.mul_n(time),
+            self.angle_velocity + derivative.angle_acceleration * time,
+        )
+    }
+}
+
+/// Auxiliary structure with the time derivatives of a motion
+struct Derivative {
+    velocity: Vec2D,
+    angle_velocity: f32,
+    acceleration: Vec2D,
+    angle_acceleration: f32,
+}
+
+impl Derivative {
+    /// Calculates the derivatives of the motion
+    fn new<F: Fn(&Motion) -> (Vec2D, f32)>(motion: &Motion, acceleration: &F) -> Self {
+        let (linear, angular) = acceleration(motion);
+
+        Derivative {
+            velocity: motion.velocity,
+            angle_velocity: motion.angle_velocity,
+            acceleration: linear,
+            angle_acceleration: angular,
+        }
+    }
+}
+
+/// Scheme of the integration of the movement of dynamic objects
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Integrator {
+    /// First order, changes the velocity first and then moves with the new velocity
+    #[default]
+    SymplecticEuler,
+    /// Second order, averages the accelerations at the start and at the end of the step
+    VelocityVerlet,
+    /// Fourth order Runge-Kutta, the most precise for position dependent accelerations
+    RungeKutta4,
+}
+
+impl Integrator {
+    /// Calculates the motion after the time
+    ///
+    /// `acceleration` returns the linear and the angular accelerations for the motion
+    pub fn integrate<F: Fn(&Motion) -> (Vec2D, f32)>(
+        &self,
+        motion: &Motion,
+        time: f32,
+        acceleration: F,
+    ) -> Motion {
+        match self {
+            Integrator::SymplecticEuler => {
+                let (linear, angular) = acceleration(motion);
+                let velocity = motion.velocity + linear.mul_n(time);
+                let angle_velocity = motion.angle_velocity + angular * time;
+
+                Motion::new(
+                    motion.position + velocity.mul_n(time),
+                    motion.angle + angle_velocity * time,
+                    velocity,
+                    angle_velocity,
+                )
+            }
+            Integrator::VelocityVerlet => {
+                let (linear, angular) = acceleration(motion);
+                let mut result = Motion::new(
+                    motion.position
+                        + motion.velocity.mul_n(time)
+                        + linear.mul_n(time * time / 2.0),
+                    motion.angle + motion.angle_velocity * time + angular * time * time / 2.0,
+                    motion.velocity + linear.mul_n(time),
+                    motion.angle_velocity + angular * time,
+                );
+
+                // the velocity is corrected by the acceleration at the end of the step
+                let (new_linear, new_angular) = acceleration(&result);
+                result.velocity = motion.velocity + (linear + new_linear).mul_n(time / 2.0);
+                result.angle_velocity =
+                    motion.angle_velocity + (angular + new_angular) * time / 2.0;
+
+                result
+            }
+            Integrator::RungeKutta4 => {
+                let k1 = Derivative::new(motion, &acceleration);
+                let k2 = Derivative::new(&motion.step(&k1, time / 2.0), &acceleration);
+                let k3 = Derivative::new(&motion.step(&k2, time / 2.0), &acceleration);
+                let k4 = Derivative::new(&motion.step(&k3, time), &acceleration);
+
+                let derivative = Derivative {
+                    velocity: (k1.velocity + (k2.velocity + k3.velocity).mul_n(2.0) + k4.velocity)
+                        .mul_n(1.0 / 6.0),
+                    angle_velocity: (k1.angle_velocity
+                        + 2.0 * (k2.angle_velocity + k3.angle_velocity)
+                        + k4.angle_velocity)
+                        / 6.0,
+                    acceleration: (k1.acceleration
+                        + (k2.acceleration + k3.acceleration).mul_n(2.0)
+                        + k4.acceleration)
+                        .mul_n(1.0 / 6.0),
+                    angle_acceleration: (k1.angle_acceleration
+                        + 2.0 * (k2.angle_acceleration + k3.angle_acceleration)
+                        + k4.angle_acceleration)
+                        / 6.0,
+                };
+
+                motion.step(&derivative, time)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        test_utils::{map_with_gravity, square},
+        traits::object_interface::ObjectInterface,
+    };
+
+    const TIME: f32 = 1.0 / 60.0;
+
+    /// Auxiliary function that integrates the free fall with a spin-up during one second
+    fn free_fall(integrator: Integrator) -> Motion {
+        let mut motion = Motion::new(Vec2D::default(), 0.0, Vec2D::new(10.0, 0.0), 0.0);
+        for _ in 0..60 {
+            motion = integrator.integrate(&motion, TIME, |_| (Vec2D::new(0.0, 100.0), 2.0));
+        }
+
+        motion
+    }
+
+    #[test]
+    fn integrators_agree_on_free_fall() {
+        let exact_position = Vec2D::new(10.0, 50.0);
+        let exact_velocity = Vec2D::new(10.0, 100.0);
+
+        for integrator in [
+            Integrator::SymplecticEuler,
+            Integrator::VelocityVerlet,
+            Integrator::RungeKutta4,
+        ] {
+            let motion = free_fall(integrator);
+
+            // all schemes find the velocity exactly under a constant acceleration
+            assert!(motion.velocity.len_vector(&exact_velocity) < 0.001, "{:?}", integrator);
+            assert!((motion.angle_velocity - 2.0).abs() < 0.001, "{:?}", integrator);
+
+            // the first order scheme is ahead by half a step of the velocity, the others are exact
+            let (position_tolerance, angle_tolerance) = match integrator {
+                Integrator::SymplecticEuler => (100.0 * TIME / 2.0 + 0.01, 2.0 * TIME / 2.0 + 0.001),
+                _ => (0.01, 0.001),
+            };
+            assert!(motion.position.len_vector(&exact_position) < position_tolerance, "{:?}", integrator);
+            assert!((motion.angle - 1.0).abs() < angle_tolerance, "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn integrators_agree_on_a_spring() {
+        // the acceleration depends on the position, so the precision of the schemes follows their order
+        let stiffness = (2.0 * std::f32::consts::PI).powi(2);
+        let spring = |motion: &Motion| (motion.position.mul_n(-stiffness), -stiffness * motion.angle);
+
+        for (integrator, position_tolerance, velocity_tolerance) in [
+            (Integrator::SymplecticEuler, 0.01, 0.5),
+            (Integrator::VelocityVerlet, 0.001, 0.5),
+            (Integrator::RungeKutta4, 0.0001, 0.01),
+        ] {
+            // one period of the oscillation brings the object back to the start
+            let mut motion = Motion::new(Vec2D::new(10.0, 0.0), 1.0, Vec2D::default(), 0.0);
+            for _ in 0..60 {
+                motion = integrator.integrate(&motion, TIME, spring);
+            }
+
+            assert!(motion.position.len_vector(&Vec2D::new(10.0, 0.0)) < position_tolerance, "{:?}", integrator);
+            assert!((motion.angle - 1.0).abs() < position_tolerance / 10.0, "{:?}", integrator);
+            assert!(motion.velocity.len_vector(&Vec2D::default()) < velocity_tolerance, "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn damping_is_the_same_for_any_step() {
+        for steps in [30, 60, 240] {
+            let mut map = map_with_gravity(Vec2D::default());
+            let mut dyn_object = square(Vec2D::new(100.0, 500.0), 20.0, 1.0);
+            dyn_object.set_velocity(Vec2D::new(100.0, 0.0));
+            dyn_object.set_linear_damping(1.0);
+            dyn_object.set_angle_velocity(1.0);
+            dyn_object.set_angle_friction(1.0);
+            map.dyn_objects.push(Box::new(dyn_object));
+            for _ in 0..steps {
+                map.run(1.0 / steps as f32);
+            }
+
+            // the velocities are damped by e after a second
+            let dyn_object = &map.dyn_objects[0];
+            assert!((dyn_object.get_velocity().x - 100.0 / 1.0_f32.exp()).abs() < 0.01, "{}", steps);
+            assert!((dyn_object.get_angle_velocity() - 1.0 / 1.0_f32.exp()).abs() < 0.001, "{}", steps);
+        }
+    }
+}
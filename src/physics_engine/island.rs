@@ -0,0 +1,103 @@
+use super::solver::ObjectId;
+
+/// Group of moving objects connected by constraints, islands don't affect each other during a step
+#[derive(Debug, Clone, Default)]
+pub struct Island {
+    /// Indices of the moving objects in ascending order
+    pub objects: Vec<usize>,
+    /// Indices of the constraints connecting the objects in ascending order
+    pub constraints: Vec<usize>,
+}
+
+impl Island {
+    /// Splits the moving objects into islands by the constraints between them
+    ///
+    /// `objects` are the indices of the moving objects that take part in the step, `constraints` are the objects connected by every constraint.
+    /// Non-moving objects don't join islands, so objects resting on the same line can be in different islands.
+    /// Constraints without a moving object of `objects` don't belong to any island
+    pub fn build(objects: &[usize], constraints: &[(ObjectId, ObjectId)]) -> Vec<Island> {
+        let count = objects.iter().max().map_or(0, |max| max + 1);
+        let mut parents: Vec<usize> = (0..count).collect();
+
+        // unites the objects of every constraint, objects after the last moving one don't take part in the step
+        for (object1, object2) in constraints {
+            if let (ObjectId::Dynamic(i), ObjectId::Dynamic(k)) = (object1, object2) {
+                if *i >= count || *k >= count {
+                    continue;
+                }
+
+                let (root1, root2) = (Island::find(&mut parents, *i), Island::find(&mut parents, *k));
+
+                // the smallest index becomes the root, so the order of islands doesn't depend on the order of constraints
+                parents[root1.max(root2)] = root1.min(root2);
+            }
+        }
+
+        let mut islands = Vec::<Island>::new();
+        let mut island_indices = vec![None; count];
+        for i in objects {
+            let root = Island::find(&mut parents, *i);
+            let index = *island_indices[root].get_or_insert_with(|| {
+                islands.push(Island::default());
+                islands.len() - 1
+            });
+            islands[index].objects.push(*i);
+        }
+
+        for (j, (object1, object2)) in constraints.iter().enumerate() {
+            let object = [object1, object2].iter().find_map(|object| match object {
+                ObjectId::Dynamic(i) if *i < count => Some(*i),
+                _ => None,
+            });
+            let Some(i) = object else {
+                continue;
+            };
+            if let Some(index) = island_indices[Island::find(&mut parents, i)] {
+                islands[index].constraints.push(j);
+            }
+        }
+
+        islands
+    }
+
+    /// Auxiliary function that finds the root object of the island, shortening the path to it
+    fn find(parents: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        let mut i = i;
+        while parents[i] != root {
+            let next = parents[i];
+            parents[i] = root;
+            i = next;
+        }
+
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_skips_constraints_without_moving_objects_of_the_step() {
+        let constraints = [
+            (ObjectId::Dynamic(0), ObjectId::Dynamic(2)),
+            (ObjectId::Dynamic(2), ObjectId::Dynamic(5)),
+            (ObjectId::Static(0), ObjectId::Dynamic(7)),
+            (ObjectId::Dynamic(1), ObjectId::Static(0)),
+            (ObjectId::Dynamic(3), ObjectId::Static(0)),
+        ];
+        let islands = Island::build(&[0, 2, 3], &constraints);
+
+        assert_eq!(islands.len(), 2);
+        assert_eq!(islands[0].objects, vec![0, 2]);
+        assert_eq!(islands[0].constraints, vec![0, 1]);
+        assert_eq!(islands[1].objects, vec![3]);
+        assert_eq!(islands[1].constraints, vec![4]);
+        assert!(Island::build(&[], &constraints).is_empty());
+    }
+}
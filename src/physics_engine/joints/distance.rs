@@ -0,0 +1,312 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::vec2d::Vec2D,
+    },
+    get_softness,
+};
+
+/// Joint that keeps the anchors of two objects at a distance from each other
+///
+/// If the min length is less than the max length, the distance can change between them (rope mode) and the length is only kept by a spring, if it has a frequency
+pub struct DistanceJoint {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    length: f32,
+    min_length: f32,
+    max_length: f32,
+    frequency: f32,
+    damping_ratio: f32,
+    arm1: Vec2D,
+    arm2: Vec2D,
+    axis: Vec2D,
+    current_length: f32,
+    mass: f32,
+    soft_mass: f32,
+    gamma: f32,
+    bias: f32,
+    impulse: f32,
+    lower_impulse: f32,
+    upper_impulse: f32,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl DistanceJoint {
+    /// Creating a rigid distance joint
+    ///
+    /// Anchors are given relative to the centers of the objects at zero angle
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        length: f32,
+    ) -> Self {
+        DistanceJoint {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            length,
+            min_length: length,
+            max_length: length,
+            frequency: 0.0,
+            damping_ratio: 0.0,
+            arm1: Vec2D::default(),
+            arm2: Vec2D::default(),
+            axis: Vec2D::default(),
+            current_length: 0.0,
+            mass: 0.0,
+            soft_mass: 0.0,
+            gamma: 0.0,
+            bias: 0.0,
+            impulse: 0.0,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    /// Length kept by the joint, when it is rigid, or the rest length of its spring
+    pub fn set_length(&mut self, length: f32) {
+        self.length = length;
+    }
+
+    pub fn get_length(&self) -> f32 {
+        self.length
+    }
+
+    /// A min length of zero turns the joint into a rope
+    pub fn set_min_length(&mut self, min_length: f32) {
+        self.min_length = min_length;
+    }
+
+    pub fn get_min_length(&self) -> f32 {
+        self.min_length
+    }
+
+    pub fn set_max_length(&mut self, max_length: f32) {
+        self.max_length = max_length;
+    }
+
+    pub fn get_max_length(&self) -> f32 {
+        self.max_length
+    }
+
+    /// Frequency of the spring in hertz, zero makes the joint rigid
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Damping ratio of the spring, one stops the oscillations the fastest
+    pub fn set_damping_ratio(&mut self, damping_ratio: f32) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    pub fn get_damping_ratio(&self) -> f32 {
+        self.damping_ratio
+    }
+
+    /// Returns the sum of the impulses of the last step, directed from the first anchor to the second one
+    pub fn get_impulse(&self) -> f32 {
+        self.impulse + self.lower_impulse - self.upper_impulse
+    }
+
+    /// Auxiliary function that checks if the distance can change between the lengths
+    fn has_limits(&self) -> bool {
+        self.min_length < self.max_length
+    }
+
+    /// Auxiliary function for calculating the inverted mass of two bodies pulled along the axis at the arms
+    fn get_inversion_mass(body1: &SolverBody, body2: &SolverBody, arm1: &Vec2D, arm2: &Vec2D, axis: &Vec2D) -> f32 {
+        let impulse_augmentation1 = Vec2D::cross(arm1, axis);
+        let impulse_augmentation2 = Vec2D::cross(arm2, axis);
+
+        body1.inversion_mass
+            + body2.inversion_mass
+            + impulse_augmentation1 * body1.inversion_inertia * impulse_augmentation1
+            + impulse_augmentation2 * body2.inversion_inertia * impulse_augmentation2
+    }
+
+    /// Auxiliary function for calculating the velocity with which the anchors move apart
+    fn get_separate_velocity(&self, body1: &SolverBody, body2: &SolverBody) -> f32 {
+        let relative_velocity = body2.velocity_at(&self.arm2) - body1.velocity_at(&self.arm1);
+
+        Vec2D::dot(&relative_velocity, &self.axis)
+    }
+
+    /// Auxiliary function that pushes the anchors apart by the impulse
+    fn apply_impulse(&self, body1: &mut SolverBody, body2: &mut SolverBody, impulse: f32) {
+        let impulse_vector = self.axis.mul_n(impulse);
+        body1.apply_impulse(&impulse_vector.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&impulse_vector, &self.arm2);
+    }
+}
+
+impl ConstraintInterface for DistanceJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        self.arm1 = body1.get_arm(&self.local_anchor1, 0.0);
+        self.arm2 = body2.get_arm(&self.local_anchor2, 0.0);
+        let distance = body2.position + self.arm2 - body1.position - self.arm1;
+        self.current_length = distance.len_vector(&Vec2D::default());
+        self.axis = distance.unit();
+
+        let inversion_mass =
+            DistanceJoint::get_inversion_mass(body1, body2, &self.arm1, &self.arm2, &self.axis);
+        self.mass = if inversion_mass == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_mass
+        };
+
+        // the spring turns the error of the length into a velocity, the softer the spring, the less of it
+        let (gamma, bias_rate) =
+            get_softness(self.mass, self.frequency, self.damping_ratio, time);
+        self.gamma = gamma;
+        self.bias = (self.current_length - self.length) * bias_rate;
+        self.soft_mass = if inversion_mass + gamma == 0.0 {
+            0.0
+        } else {
+            1.0 / (inversion_mass + gamma)
+        };
+
+        // limits that are not reached don't keep their impulses
+        if !self.has_limits() {
+            self.lower_impulse = 0.0;
+            self.upper_impulse = 0.0;
+        }
+
+        self.apply_impulse(body1, body2, self.get_impulse());
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        // keeps the length, softly if the joint has a spring
+        if self.frequency > 0.0 {
+            let separate_velocity = self.get_separate_velocity(body1, body2);
+            let impulse =
+                -self.soft_mass * (separate_velocity + self.bias + self.gamma * self.impulse);
+            self.impulse += impulse;
+            self.apply_impulse(body1, body2, impulse);
+        } else if !self.has_limits() {
+            let impulse = -self.mass * self.get_separate_velocity(body1, body2);
+            self.impulse += impulse;
+            self.apply_impulse(body1, body2, impulse);
+        }
+
+        if !self.has_limits() {
+            return;
+        }
+
+        // anchors may approach the min length during the step, but not pass it
+        let separate_velocity = self.get_separate_velocity(body1, body2);
+        let error = (self.current_length - self.min_length).max(0.0);
+        let impulse = -self.mass * (separate_velocity + error / self.time);
+        let new_impulse = (self.lower_impulse + impulse).max(0.0);
+        let impulse = new_impulse - self.lower_impulse;
+        self.lower_impulse = new_impulse;
+        self.apply_impulse(body1, body2, impulse);
+
+        // same for the max length, the impulse of which pulls the anchors together
+        let separate_velocity = self.get_separate_velocity(body1, body2);
+        let error = (self.max_length - self.current_length).max(0.0);
+        let impulse = -self.mass * (-separate_velocity + error / self.time);
+        let new_impulse = (self.upper_impulse + impulse).max(0.0);
+        let impulse = new_impulse - self.upper_impulse;
+        self.upper_impulse = new_impulse;
+        self.apply_impulse(body1, body2, -impulse);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // the error is calculated for the positions at the end of the step
+        let arm1 = body1.get_arm(&self.local_anchor1, self.time);
+        let arm2 = body2.get_arm(&self.local_anchor2, self.time);
+        let distance = body2.get_position(self.time) + arm2 - body1.get_position(self.time) - arm1;
+        let length = distance.len_vector(&Vec2D::default());
+        let axis = distance.unit();
+
+        // a spring is allowed to stretch, only the limits are kept strictly
+        let error = if !self.has_limits() && self.frequency <= 0.0 {
+            length - self.length
+        } else if self.has_limits() && length < self.min_length {
+            length - self.min_length
+        } else if self.has_limits() && length > self.max_length {
+            length - self.max_length
+        } else {
+            return;
+        };
+
+        let correction = solver.get_joint_correction(error.abs()) * error.signum();
+        let inversion_mass = DistanceJoint::get_inversion_mass(body1, body2, &arm1, &arm2, &axis);
+        if correction == 0.0 || inversion_mass == 0.0 {
+            return;
+        }
+
+        let displacement = axis.mul_n(correction / inversion_mass);
+        body1.apply_displacement(&displacement, &arm1);
+        body2.apply_displacement(&displacement.mul_n(-1.0), &arm2);
+    }
+}
+
+impl JointInterface for DistanceJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::{map_with_gravity, square};
+
+    #[test]
+    fn distance_joint_keeps_the_length() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(600.0, 100.0), 20.0, 1.0)));
+
+        // the center of the box swings around the point in the air, the top border holds the point
+        let point = Vec2D::new(500.0, 100.0);
+        map.add_joint(Box::new(DistanceJoint::new(
+            ObjectId::Static(0),
+            ObjectId::Dynamic(0),
+            map.get_local_point(ObjectId::Static(0), point),
+            Vec2D::default(),
+            100.0,
+        )));
+
+        let mut max_error: f32 = 0.0;
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+
+            let length = map.dyn_objects[0].get_current_position().len_vector(&point);
+            max_error = max_error.max((length - 100.0).abs());
+        }
+        assert!(max_error < 0.1);
+    }
+}
@@ -0,0 +1,179 @@
+use std::f32::consts::PI;
+
+use super::{
+    solver::SolverBody,
+    types::{angle::Angle, vec2d::Vec2D},
+};
+
+// joints are warm started like contacts: `prepare` applies the impulses accumulated in the previous step, so that joints holding
+// a load converge in few iterations, while disabled motors and limits drop their impulses, so that they don't push once they are enabled again
+
+pub mod distance;
+
+pub mod revolute;
+
+pub mod prismatic;
+
+pub mod wheel;
+
+pub mod weld;
+
+pub mod mouse;
+
+/// Stable reference to a joint of the map, handles of removed joints are never reused
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointHandle(pub usize);
+
+/// Calculates the softness of a spring constraint from its frequency in hertz and its damping ratio
+///
+/// Returns `gamma`, the part of the accumulated impulse added to the velocity error, and the part of the position error added to the velocity error per step.
+/// A zero frequency makes the constraint rigid
+pub fn get_softness(mass: f32, frequency: f32, damping_ratio: f32, time: f32) -> (f32, f32) {
+    if frequency <= 0.0 || mass == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    // calculates the stiffness and the damping of a spring with the given natural frequency
+    let omega = 2.0 * PI * frequency;
+    let stiffness = mass * omega * omega;
+    let damping = 2.0 * mass * damping_ratio * omega;
+
+    get_spring_softness(stiffness, damping, time)
+}
+
+/// Same as `get_softness`, but for a spring given by its stiffness and damping
+///
+/// The spring is integrated implicitly, so it stays stable at any stiffness
+pub fn get_spring_softness(stiffness: f32, damping: f32, time: f32) -> (f32, f32) {
+    let gamma = time * (damping + time * stiffness);
+    let gamma = if gamma == 0.0 { 0.0 } else { 1.0 / gamma };
+
+    (gamma, time * stiffness * gamma)
+}
+
+/// Calculates the angle of the second body relative to the first one minus the reference angle, for the bodies turned for the time
+///
+/// The angle is in the range from -PI to PI, so that the joints turn the bodies along the shortest arc
+pub fn get_relative_angle(body1: &SolverBody, body2: &SolverBody, reference_angle: f32, time: f32) -> f32 {
+    let angle1 = body1.angle + body1.angle_velocity * time;
+    let angle2 = body2.angle + body2.angle_velocity * time;
+
+    Angle::new(angle2 - angle1 - reference_angle).get_signed_radian()
+}
+
+/// Calculates the impulse that removes the relative velocity (or displacement) of the points of two bodies located at the arms
+///
+/// `error` is the velocity of the point of the second body relative to the point of the first one, the impulse is applied to the second body.
+/// `gamma` softens the constraint, as returned by `get_softness`
+pub fn get_point_impulse(
+    body1: &SolverBody,
+    body2: &SolverBody,
+    arm1: &Vec2D,
+    arm2: &Vec2D,
+    error: &Vec2D,
+    gamma: f32,
+) -> Vec2D {
+    let inversion_mass = body1.inversion_mass + body2.inversion_mass + gamma;
+    let (i1, i2) = (body1.inversion_inertia, body2.inversion_inertia);
+
+    // the matrix of the inverted mass of the points
+    let a11 = inversion_mass + arm1.y * arm1.y * i1 + arm2.y * arm2.y * i2;
+    let a12 = -arm1.y * arm1.x * i1 - arm2.y * arm2.x * i2;
+    let a22 = inversion_mass + arm1.x * arm1.x * i1 + arm2.x * arm2.x * i2;
+
+    let determinant = a11 * a22 - a12 * a12;
+    if determinant == 0.0 {
+        return Vec2D::default();
+    }
+
+    Vec2D::new(
+        -(a22 * error.x - a12 * error.y) / determinant,
+        -(a11 * error.y - a12 * error.x) / determinant,
+    )
+}
+
+/// Direction along which a joint pushes the anchors of two bodies apart, the first anchor can slide along the direction
+#[derive(Debug, Copy, Clone, Default)]
+pub struct JointAxis {
+    pub direction: Vec2D,
+    pub impulse_augmentation1: f32,
+    pub impulse_augmentation2: f32,
+    pub mass: f32,
+}
+
+impl JointAxis {
+    /// Creating an axis for the anchors located at the arms, `distance` goes from the first anchor to the second one
+    pub fn new(
+        body1: &SolverBody,
+        body2: &SolverBody,
+        arm1: &Vec2D,
+        arm2: &Vec2D,
+        distance: &Vec2D,
+        direction: Vec2D,
+    ) -> Self {
+        // the first body is pushed at its anchor moved by the distance, so it also turns when the anchors slide apart
+        let impulse_augmentation1 = Vec2D::cross(&(*distance + *arm1), &direction);
+        let impulse_augmentation2 = Vec2D::cross(arm2, &direction);
+        let inversion_mass = body1.inversion_mass
+            + body2.inversion_mass
+            + impulse_augmentation1 * body1.inversion_inertia * impulse_augmentation1
+            + impulse_augmentation2 * body2.inversion_inertia * impulse_augmentation2;
+
+        JointAxis {
+            direction,
+            impulse_augmentation1,
+            impulse_augmentation2,
+            mass: if inversion_mass == 0.0 {
+                0.0
+            } else {
+                1.0 / inversion_mass
+            },
+        }
+    }
+
+    /// Calculates the velocity with which the anchors move apart along the axis
+    pub fn get_velocity(&self, body1: &SolverBody, body2: &SolverBody) -> f32 {
+        Vec2D::dot(&(body2.velocity - body1.velocity), &self.direction)
+            + self.impulse_augmentation2 * body2.angle_velocity
+            - self.impulse_augmentation1 * body1.angle_velocity
+    }
+
+    /// Pushes the anchors apart by the impulse along the axis
+    pub fn apply_impulse(&self, body1: &mut SolverBody, body2: &mut SolverBody, impulse: f32) {
+        body1.velocity -= self.direction.mul_n(impulse * body1.inversion_mass);
+        body1.angle_velocity -= impulse * self.impulse_augmentation1 * body1.inversion_inertia;
+        body2.velocity += self.direction.mul_n(impulse * body2.inversion_mass);
+        body2.angle_velocity += impulse * self.impulse_augmentation2 * body2.inversion_inertia;
+    }
+
+    /// Moves the anchors apart by the positional impulse along the axis
+    pub fn apply_displacement(&self, body1: &mut SolverBody, body2: &mut SolverBody, impulse: f32) {
+        body1.position -= self.direction.mul_n(impulse * body1.inversion_mass);
+        body1.angle -= impulse * self.impulse_augmentation1 * body1.inversion_inertia;
+        body2.position += self.direction.mul_n(impulse * body2.inversion_mass);
+        body2.angle += impulse * self.impulse_augmentation2 * body2.inversion_inertia;
+    }
+}
+
+/// Calculates the axis of a sliding joint given relative to the first body and the axis perpendicular to it, for the bodies moved for the time
+///
+/// Also returns the distance from the first anchor to the second one
+pub fn get_axes(
+    body1: &SolverBody,
+    body2: &SolverBody,
+    local_anchor1: &Vec2D,
+    local_anchor2: &Vec2D,
+    local_axis: &Vec2D,
+    time: f32,
+) -> (JointAxis, JointAxis, Vec2D) {
+    let arm1 = body1.get_arm(local_anchor1, time);
+    let arm2 = body2.get_arm(local_anchor2, time);
+    let distance = body2.get_position(time) + arm2 - body1.get_position(time) - arm1;
+    let direction = body1.get_arm(local_axis, time);
+
+    (
+        JointAxis::new(body1, body2, &arm1, &arm2, &distance, direction),
+        JointAxis::new(body1, body2, &arm1, &arm2, &distance, direction.normal()),
+        distance,
+    )
+}
@@ -0,0 +1,206 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::vec2d::Vec2D,
+    },
+    get_point_impulse, get_softness,
+};
+
+/// Joint that softly pulls the anchor of an object to the target point of the map, used for dragging objects
+///
+/// The pull is limited by the max force, so the object can't be dragged through walls
+pub struct MouseJoint {
+    object: ObjectId,
+    local_anchor: Vec2D,
+    target: Vec2D,
+    max_force: f32,
+    frequency: f32,
+    damping_ratio: f32,
+    arm: Vec2D,
+    gamma: f32,
+    bias: Vec2D,
+    impulse: Vec2D,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl MouseJoint {
+    /// Creating a mouse joint, the anchor is given relative to the center of the object at zero angle
+    pub fn new(object: ObjectId, local_anchor: Vec2D, target: Vec2D, max_force: f32) -> Self {
+        MouseJoint {
+            object,
+            local_anchor,
+            target,
+            max_force,
+            frequency: 5.0,
+            damping_ratio: 0.7,
+            arm: Vec2D::default(),
+            gamma: 0.0,
+            bias: Vec2D::default(),
+            impulse: Vec2D::default(),
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor(&self) -> Vec2D {
+        self.local_anchor
+    }
+
+    /// Point of the map to which the anchor is pulled, it can be moved at any time
+    pub fn set_target(&mut self, target: Vec2D) {
+        self.target = target;
+    }
+
+    pub fn get_target(&self) -> Vec2D {
+        self.target
+    }
+
+    pub fn set_max_force(&mut self, max_force: f32) {
+        self.max_force = max_force;
+    }
+
+    pub fn get_max_force(&self) -> f32 {
+        self.max_force
+    }
+
+    /// Frequency of the pull in hertz, the higher it is, the faster the anchor follows the target
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn set_damping_ratio(&mut self, damping_ratio: f32) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    pub fn get_damping_ratio(&self) -> f32 {
+        self.damping_ratio
+    }
+
+    /// Returns the force with which the object was pulled at the last step
+    pub fn get_force(&self) -> Vec2D {
+        if self.time == 0.0 {
+            Vec2D::default()
+        } else {
+            self.impulse.mul_n(1.0 / self.time)
+        }
+    }
+}
+
+impl ConstraintInterface for MouseJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (ObjectId::World, self.object)
+    }
+
+    fn prepare(&mut self, _body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        self.arm = body2.get_arm(&self.local_anchor, 0.0);
+
+        // the pull is a spring between the anchor and the target
+        let mass = if body2.inversion_mass == 0.0 {
+            0.0
+        } else {
+            1.0 / body2.inversion_mass
+        };
+        let (gamma, bias_rate) = get_softness(mass, self.frequency, self.damping_ratio, time);
+        self.gamma = gamma;
+        self.bias = (body2.position + self.arm - self.target).mul_n(bias_rate);
+
+        body2.apply_impulse(&self.impulse, &self.arm);
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        let velocity = body2.velocity_at(&self.arm);
+        let impulse = get_point_impulse(
+            body1,
+            body2,
+            &Vec2D::default(),
+            &self.arm,
+            &(velocity + self.bias + self.impulse.mul_n(self.gamma)),
+            self.gamma,
+        );
+
+        // the accumulated impulse can't exceed the max force during the step
+        let max_impulse = self.max_force * self.time;
+        let mut new_impulse = self.impulse + impulse;
+        if new_impulse.len_vector(&Vec2D::default()) > max_impulse {
+            new_impulse = new_impulse.unit().mul_n(max_impulse);
+        }
+        let impulse = new_impulse - self.impulse;
+        self.impulse = new_impulse;
+
+        body2.apply_impulse(&impulse, &self.arm);
+    }
+
+    fn solve_position(&mut self, _body1: &mut SolverBody, _body2: &mut SolverBody, _solver: &Solver) {
+        // the anchor follows the target softly, so there is nothing to correct
+    }
+}
+
+impl JointInterface for MouseJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::{map_with_gravity, square};
+
+    #[test]
+    fn mouse_joint_drags_the_object_to_the_moving_target() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(300.0, 500.0), 20.0, 1.0)));
+        let handle = map.add_joint(Box::new(MouseJoint::new(
+            ObjectId::Dynamic(0),
+            Vec2D::default(),
+            Vec2D::new(400.0, 500.0),
+            1.0e5,
+        )));
+
+        for target in [Vec2D::new(400.0, 500.0), Vec2D::new(400.0, 300.0)] {
+            map.get_mut_joint::<MouseJoint>(handle).unwrap().set_target(target);
+            for _ in 0..60 {
+                map.run(1.0 / 60.0);
+            }
+
+            // the soft pull leaves the anchor a bit under the target, where the joint holds the weight
+            let position = map.dyn_objects[0].get_current_position();
+            assert!(position.len_vector(&target) < 1.0, "{:?}", position);
+            let force = map.get_joint::<MouseJoint>(handle).unwrap().get_force();
+            assert!(force.len_vector(&Vec2D::new(0.0, -100.0)) < 1.0, "{:?}", force);
+        }
+    }
+
+    #[test]
+    fn mouse_joint_does_not_exceed_the_max_force() {
+        // the pull is weaker than the weight, so the box stays on the floor
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(500.0, 990.0), 20.0, 1.0)));
+        let handle = map.add_joint(Box::new(MouseJoint::new(
+            ObjectId::Dynamic(0),
+            Vec2D::default(),
+            Vec2D::new(500.0, 500.0),
+            50.0,
+        )));
+        for _ in 0..120 {
+            map.run(1.0 / 60.0);
+        }
+
+        assert!((map.dyn_objects[0].get_current_position().y - 990.0).abs() < 1.0);
+        let force = map.get_joint::<MouseJoint>(handle).unwrap().get_force();
+        assert!((force.len_vector(&Vec2D::default()) - 50.0).abs() < 0.01, "{:?}", force);
+    }
+}
@@ -0,0 +1,344 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::{angle::Angle, vec2d::Vec2D},
+    },
+    get_axes, get_relative_angle, JointAxis,
+};
+
+/// Joint that lets the second object slide along the axis of the first one without turning relative to it
+///
+/// The translation can be limited and driven by a motor
+pub struct PrismaticJoint {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    local_axis: Vec2D,
+    reference_angle: f32,
+    enable_limit: bool,
+    lower_translation: f32,
+    upper_translation: f32,
+    enable_motor: bool,
+    motor_speed: f32,
+    max_motor_force: f32,
+    axis: JointAxis,
+    perpendicular: JointAxis,
+    translation: f32,
+    axial_mass: f32,
+    impulse: f32,
+    angle_impulse: f32,
+    motor_impulse: f32,
+    lower_impulse: f32,
+    upper_impulse: f32,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl PrismaticJoint {
+    /// Creating a prismatic joint
+    ///
+    /// Anchors and the axis are given relative to the objects at zero angle, `reference_angle` is the angle of the second object relative to the first one that is kept
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        local_axis: Vec2D,
+        reference_angle: Angle,
+    ) -> Self {
+        PrismaticJoint {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            local_axis: local_axis.unit(),
+            reference_angle: reference_angle.get_radian(),
+            enable_limit: false,
+            lower_translation: 0.0,
+            upper_translation: 0.0,
+            enable_motor: false,
+            motor_speed: 0.0,
+            max_motor_force: 0.0,
+            axis: JointAxis::default(),
+            perpendicular: JointAxis::default(),
+            translation: 0.0,
+            axial_mass: 0.0,
+            impulse: 0.0,
+            angle_impulse: 0.0,
+            motor_impulse: 0.0,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    pub fn get_local_axis(&self) -> Vec2D {
+        self.local_axis
+    }
+
+    pub fn get_reference_angle(&self) -> Angle {
+        Angle::new(self.reference_angle)
+    }
+
+    pub fn set_enable_limit(&mut self, enable_limit: bool) {
+        self.enable_limit = enable_limit;
+    }
+
+    pub fn get_enable_limit(&self) -> bool {
+        self.enable_limit
+    }
+
+    /// Sets the range of the translation of the second anchor along the axis from the first anchor
+    pub fn set_limits(&mut self, lower_translation: f32, upper_translation: f32) {
+        self.lower_translation = lower_translation;
+        self.upper_translation = upper_translation;
+    }
+
+    pub fn get_limits(&self) -> (f32, f32) {
+        (self.lower_translation, self.upper_translation)
+    }
+
+    pub fn set_enable_motor(&mut self, enable_motor: bool) {
+        self.enable_motor = enable_motor;
+    }
+
+    pub fn get_enable_motor(&self) -> bool {
+        self.enable_motor
+    }
+
+    /// Velocity of the second object along the axis relative to the first one that the motor tries to keep
+    pub fn set_motor_speed(&mut self, motor_speed: f32) {
+        self.motor_speed = motor_speed;
+    }
+
+    pub fn get_motor_speed(&self) -> f32 {
+        self.motor_speed
+    }
+
+    pub fn set_max_motor_force(&mut self, max_motor_force: f32) {
+        self.max_motor_force = max_motor_force;
+    }
+
+    pub fn get_max_motor_force(&self) -> f32 {
+        self.max_motor_force
+    }
+
+    /// Returns the translation of the second anchor along the axis at the last step
+    pub fn get_translation(&self) -> f32 {
+        self.translation
+    }
+
+    /// Returns the force of the motor at the last step
+    pub fn get_motor_force(&self) -> f32 {
+        if self.time == 0.0 {
+            0.0
+        } else {
+            self.motor_impulse / self.time
+        }
+    }
+}
+
+impl ConstraintInterface for PrismaticJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        let (axis, perpendicular, distance) = get_axes(
+            body1,
+            body2,
+            &self.local_anchor1,
+            &self.local_anchor2,
+            &self.local_axis,
+            0.0,
+        );
+        self.axis = axis;
+        self.perpendicular = perpendicular;
+        self.translation = Vec2D::dot(&distance, &axis.direction);
+
+        let inversion_inertia = body1.inversion_inertia + body2.inversion_inertia;
+        self.axial_mass = if inversion_inertia == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_inertia
+        };
+
+        if !self.enable_motor {
+            self.motor_impulse = 0.0;
+        }
+        if !self.enable_limit {
+            self.lower_impulse = 0.0;
+            self.upper_impulse = 0.0;
+        }
+
+        self.axis.apply_impulse(
+            body1,
+            body2,
+            self.motor_impulse + self.lower_impulse - self.upper_impulse,
+        );
+        self.perpendicular.apply_impulse(body1, body2, self.impulse);
+        body1.apply_angular_impulse(-self.angle_impulse);
+        body2.apply_angular_impulse(self.angle_impulse);
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        // the motor can't exceed its force during the step
+        if self.enable_motor {
+            let velocity = self.axis.get_velocity(body1, body2) - self.motor_speed;
+            let max_impulse = self.max_motor_force * self.time;
+            let new_impulse =
+                (self.motor_impulse - self.axis.mass * velocity).clamp(-max_impulse, max_impulse);
+            let impulse = new_impulse - self.motor_impulse;
+            self.motor_impulse = new_impulse;
+            self.axis.apply_impulse(body1, body2, impulse);
+        }
+
+        // the translation may approach the limits during the step, but not pass them
+        if self.enable_limit {
+            let error = (self.translation - self.lower_translation).max(0.0);
+            let velocity = self.axis.get_velocity(body1, body2);
+            let impulse = -self.axis.mass * (velocity + error / self.time);
+            let new_impulse = (self.lower_impulse + impulse).max(0.0);
+            let impulse = new_impulse - self.lower_impulse;
+            self.lower_impulse = new_impulse;
+            self.axis.apply_impulse(body1, body2, impulse);
+
+            let error = (self.upper_translation - self.translation).max(0.0);
+            let velocity = -self.axis.get_velocity(body1, body2);
+            let impulse = -self.axis.mass * (velocity + error / self.time);
+            let new_impulse = (self.upper_impulse + impulse).max(0.0);
+            let impulse = new_impulse - self.upper_impulse;
+            self.upper_impulse = new_impulse;
+            self.axis.apply_impulse(body1, body2, -impulse);
+        }
+
+        // keeps the anchors on the axis and the objects from turning
+        let impulse = -self.perpendicular.mass * self.perpendicular.get_velocity(body1, body2);
+        self.impulse += impulse;
+        self.perpendicular.apply_impulse(body1, body2, impulse);
+
+        let impulse = -self.axial_mass * (body2.angle_velocity - body1.angle_velocity);
+        self.angle_impulse += impulse;
+        body1.apply_angular_impulse(-impulse);
+        body2.apply_angular_impulse(impulse);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // the errors are calculated for the positions at the end of the step
+        let error = get_relative_angle(body1, body2, self.reference_angle, self.time);
+        let impulse = -self.axial_mass * error * solver.get_correction_factor();
+        body1.apply_angular_displacement(-impulse);
+        body2.apply_angular_displacement(impulse);
+
+        let (axis, perpendicular, distance) = get_axes(
+            body1,
+            body2,
+            &self.local_anchor1,
+            &self.local_anchor2,
+            &self.local_axis,
+            self.time,
+        );
+        let error = Vec2D::dot(&distance, &perpendicular.direction);
+        let correction = solver.get_joint_correction(error.abs()) * error.signum();
+        perpendicular.apply_displacement(body1, body2, -perpendicular.mass * correction);
+
+        if self.enable_limit {
+            let translation = Vec2D::dot(&distance, &axis.direction);
+            let error = if translation < self.lower_translation {
+                translation - self.lower_translation
+            } else if translation > self.upper_translation {
+                translation - self.upper_translation
+            } else {
+                0.0
+            };
+
+            let correction = solver.get_joint_correction(error.abs()) * error.signum();
+            axis.apply_displacement(body1, body2, -axis.mass * correction);
+        }
+    }
+}
+
+impl JointInterface for PrismaticJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        joints::JointHandle,
+        map::Map,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that hangs a box at the point on a vertical prismatic joint of the top border
+    fn hanging_box(gravity: Vec2D, point: Vec2D) -> (Map, JointHandle) {
+        let mut map = map_with_gravity(gravity);
+        map.dyn_objects.push(Box::new(square(point, 20.0, 1.0)));
+
+        let (object1, object2) = (ObjectId::Static(0), ObjectId::Dynamic(0));
+        let joint = PrismaticJoint::new(
+            object1,
+            object2,
+            map.get_local_point(object1, point),
+            Vec2D::default(),
+            Vec2D::new(0.0, 1.0),
+            map.get_relative_angle(object1, object2),
+        );
+        let handle = map.add_joint(Box::new(joint));
+
+        (map, handle)
+    }
+
+    #[test]
+    fn prismatic_joint_stops_at_the_limit() {
+        let (mut map, handle) = hanging_box(Vec2D::new(100.0, 100.0), Vec2D::new(500.0, 100.0));
+        let joint = map.get_mut_joint::<PrismaticJoint>(handle).unwrap();
+        joint.set_enable_limit(true);
+        joint.set_limits(-10.0, 50.0);
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the sideways gravity is held by the axis, the box doesn't turn
+        let dyn_object = &map.dyn_objects[0];
+        assert!(dyn_object.get_current_position().len_vector(&Vec2D::new(500.0, 150.0)) < 0.1);
+        assert!(dyn_object.get_angle().get_signed_radian().abs() < 0.01);
+        assert!((map.get_joint::<PrismaticJoint>(handle).unwrap().get_translation() - 50.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn prismatic_motor_lifts_the_box() {
+        let (mut map, handle) = hanging_box(Vec2D::new(0.0, 100.0), Vec2D::new(500.0, 500.0));
+        let joint = map.get_mut_joint::<PrismaticJoint>(handle).unwrap();
+        joint.set_enable_motor(true);
+        joint.set_motor_speed(-20.0);
+        joint.set_max_motor_force(1000.0);
+        for _ in 0..60 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the motor holds the weight of the box besides its speed
+        let motor_force = map.get_joint::<PrismaticJoint>(handle).unwrap().get_motor_force();
+        assert!(map.dyn_objects[0].get_velocity().len_vector(&Vec2D::new(0.0, -20.0)) < 0.1);
+        assert!((motor_force.abs() - 100.0).abs() < 1.0);
+    }
+}
@@ -0,0 +1,305 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::{angle::Angle, vec2d::Vec2D},
+    },
+    get_point_impulse, get_relative_angle,
+};
+
+/// Joint that pins the anchors of two objects together, letting them turn around the common point
+///
+/// The turn can be limited by angles and driven by a motor
+pub struct RevoluteJoint {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    reference_angle: f32,
+    enable_limit: bool,
+    lower_angle: Angle,
+    upper_angle: Angle,
+    enable_motor: bool,
+    motor_speed: f32,
+    max_motor_torque: f32,
+    arm1: Vec2D,
+    arm2: Vec2D,
+    joint_angle: f32,
+    axial_mass: f32,
+    impulse: Vec2D,
+    motor_impulse: f32,
+    lower_impulse: f32,
+    upper_impulse: f32,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl RevoluteJoint {
+    /// Creating a revolute joint
+    ///
+    /// Anchors are given relative to the centers of the objects at zero angle, `reference_angle` is the angle of the second object relative to the first one at which the joint angle is zero
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        reference_angle: Angle,
+    ) -> Self {
+        RevoluteJoint {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            reference_angle: reference_angle.get_radian(),
+            enable_limit: false,
+            lower_angle: Angle::default(),
+            upper_angle: Angle::default(),
+            enable_motor: false,
+            motor_speed: 0.0,
+            max_motor_torque: 0.0,
+            arm1: Vec2D::default(),
+            arm2: Vec2D::default(),
+            joint_angle: 0.0,
+            axial_mass: 0.0,
+            impulse: Vec2D::default(),
+            motor_impulse: 0.0,
+            lower_impulse: 0.0,
+            upper_impulse: 0.0,
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    pub fn get_reference_angle(&self) -> Angle {
+        Angle::new(self.reference_angle)
+    }
+
+    pub fn set_enable_limit(&mut self, enable_limit: bool) {
+        self.enable_limit = enable_limit;
+    }
+
+    pub fn get_enable_limit(&self) -> bool {
+        self.enable_limit
+    }
+
+    /// Sets the range of the joint angle, the limits are taken in the range from -π to π
+    pub fn set_limits(&mut self, lower_angle: Angle, upper_angle: Angle) {
+        self.lower_angle = lower_angle;
+        self.upper_angle = upper_angle;
+    }
+
+    pub fn get_limits(&self) -> (Angle, Angle) {
+        (self.lower_angle, self.upper_angle)
+    }
+
+    pub fn set_enable_motor(&mut self, enable_motor: bool) {
+        self.enable_motor = enable_motor;
+    }
+
+    pub fn get_enable_motor(&self) -> bool {
+        self.enable_motor
+    }
+
+    /// Angle velocity of the second object relative to the first one that the motor tries to keep
+    pub fn set_motor_speed(&mut self, motor_speed: f32) {
+        self.motor_speed = motor_speed;
+    }
+
+    pub fn get_motor_speed(&self) -> f32 {
+        self.motor_speed
+    }
+
+    pub fn set_max_motor_torque(&mut self, max_motor_torque: f32) {
+        self.max_motor_torque = max_motor_torque;
+    }
+
+    pub fn get_max_motor_torque(&self) -> f32 {
+        self.max_motor_torque
+    }
+
+    /// Returns the angle of the second object relative to the first one at the last step, minus the reference angle
+    pub fn get_joint_angle(&self) -> Angle {
+        Angle::new(self.joint_angle)
+    }
+
+    /// Returns the torque of the motor at the last step
+    pub fn get_motor_torque(&self) -> f32 {
+        if self.time == 0.0 {
+            0.0
+        } else {
+            self.motor_impulse / self.time
+        }
+    }
+
+    /// Returns the impulse that kept the anchors together at the last step, applied to the second object
+    pub fn get_impulse(&self) -> Vec2D {
+        self.impulse
+    }
+
+    /// Auxiliary function that turns the bodies in opposite directions by the angular impulse
+    fn apply_angular_impulse(body1: &mut SolverBody, body2: &mut SolverBody, impulse: f32) {
+        body1.apply_angular_impulse(-impulse);
+        body2.apply_angular_impulse(impulse);
+    }
+}
+
+impl ConstraintInterface for RevoluteJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        self.arm1 = body1.get_arm(&self.local_anchor1, 0.0);
+        self.arm2 = body2.get_arm(&self.local_anchor2, 0.0);
+        self.joint_angle = get_relative_angle(body1, body2, self.reference_angle, 0.0);
+
+        let inversion_inertia = body1.inversion_inertia + body2.inversion_inertia;
+        self.axial_mass = if inversion_inertia == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_inertia
+        };
+
+        if !self.enable_motor {
+            self.motor_impulse = 0.0;
+        }
+        if !self.enable_limit {
+            self.lower_impulse = 0.0;
+            self.upper_impulse = 0.0;
+        }
+
+        body1.apply_impulse(&self.impulse.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&self.impulse, &self.arm2);
+        RevoluteJoint::apply_angular_impulse(
+            body1,
+            body2,
+            self.motor_impulse + self.lower_impulse - self.upper_impulse,
+        );
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        // the motor can't exceed its torque during the step
+        if self.enable_motor {
+            let angle_velocity = body2.angle_velocity - body1.angle_velocity - self.motor_speed;
+            let max_impulse = self.max_motor_torque * self.time;
+            let new_impulse =
+                (self.motor_impulse - self.axial_mass * angle_velocity).clamp(-max_impulse, max_impulse);
+            let impulse = new_impulse - self.motor_impulse;
+            self.motor_impulse = new_impulse;
+            RevoluteJoint::apply_angular_impulse(body1, body2, impulse);
+        }
+
+        // the joint angle may approach the limits during the step, but not pass them
+        if self.enable_limit {
+            let error = (self.joint_angle - self.lower_angle.get_signed_radian()).max(0.0);
+            let angle_velocity = body2.angle_velocity - body1.angle_velocity;
+            let impulse = -self.axial_mass * (angle_velocity + error / self.time);
+            let new_impulse = (self.lower_impulse + impulse).max(0.0);
+            let impulse = new_impulse - self.lower_impulse;
+            self.lower_impulse = new_impulse;
+            RevoluteJoint::apply_angular_impulse(body1, body2, impulse);
+
+            let error = (self.upper_angle.get_signed_radian() - self.joint_angle).max(0.0);
+            let angle_velocity = body1.angle_velocity - body2.angle_velocity;
+            let impulse = -self.axial_mass * (angle_velocity + error / self.time);
+            let new_impulse = (self.upper_impulse + impulse).max(0.0);
+            let impulse = new_impulse - self.upper_impulse;
+            self.upper_impulse = new_impulse;
+            RevoluteJoint::apply_angular_impulse(body1, body2, -impulse);
+        }
+
+        // keeps the anchors moving together
+        let relative_velocity = body2.velocity_at(&self.arm2) - body1.velocity_at(&self.arm1);
+        let impulse = get_point_impulse(body1, body2, &self.arm1, &self.arm2, &relative_velocity, 0.0);
+        self.impulse += impulse;
+        body1.apply_impulse(&impulse.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&impulse, &self.arm2);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // the errors are calculated for the positions at the end of the step
+        if self.enable_limit {
+            let joint_angle = get_relative_angle(body1, body2, self.reference_angle, self.time);
+            let error = if joint_angle < self.lower_angle.get_signed_radian() {
+                joint_angle - self.lower_angle.get_signed_radian()
+            } else if joint_angle > self.upper_angle.get_signed_radian() {
+                joint_angle - self.upper_angle.get_signed_radian()
+            } else {
+                0.0
+            };
+
+            let impulse = -self.axial_mass * error * solver.get_correction_factor();
+            body1.apply_angular_displacement(-impulse);
+            body2.apply_angular_displacement(impulse);
+        }
+
+        let arm1 = body1.get_arm(&self.local_anchor1, self.time);
+        let arm2 = body2.get_arm(&self.local_anchor2, self.time);
+        let error = body2.get_position(self.time) + arm2 - body1.get_position(self.time) - arm1;
+        let correction = error
+            .unit()
+            .mul_n(solver.get_joint_correction(error.len_vector(&Vec2D::default())));
+
+        let impulse = get_point_impulse(body1, body2, &arm1, &arm2, &correction, 0.0);
+        body1.apply_displacement(&impulse.mul_n(-1.0), &arm1);
+        body2.apply_displacement(&impulse, &arm2);
+    }
+}
+
+impl JointInterface for RevoluteJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        test_utils::{map_with_gravity, square},
+        types::matrix2d::Matrix2D,
+    };
+
+    #[test]
+    fn revolute_joint_keeps_the_anchors_together() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(600.0, 100.0), 20.0, 1.0)));
+
+        // the box hangs on an arm from the pivot in the air, the top border holds the pivot
+        let pivot = Vec2D::new(500.0, 100.0);
+        let (object1, object2) = (ObjectId::Static(0), ObjectId::Dynamic(0));
+        let local_anchor = map.get_local_point(object2, pivot);
+        map.add_joint(Box::new(RevoluteJoint::new(
+            object1,
+            object2,
+            map.get_local_point(object1, pivot),
+            local_anchor,
+            Angle::default(),
+        )));
+
+        let mut max_error: f32 = 0.0;
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+
+            let dyn_object = &map.dyn_objects[0];
+            let anchor = dyn_object.get_current_position()
+                + Matrix2D::rotation_matrix(&dyn_object.get_angle()).multiply_vec2d(&local_anchor);
+            max_error = max_error.max(anchor.len_vector(&pivot));
+        }
+        assert!(max_error < 0.1);
+    }
+}
@@ -0,0 +1,262 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::{angle::Angle, vec2d::Vec2D},
+    },
+    get_point_impulse, get_relative_angle, get_softness,
+};
+
+/// Joint that glues two objects together, keeping their relative position and angle
+///
+/// With a frequency the objects are held by springs and can bend and stretch the joint
+pub struct WeldJoint {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    reference_angle: f32,
+    frequency: f32,
+    damping_ratio: f32,
+    arm1: Vec2D,
+    arm2: Vec2D,
+    linear_gamma: f32,
+    linear_bias: Vec2D,
+    angle_gamma: f32,
+    angle_bias: f32,
+    axial_mass: f32,
+    impulse: Vec2D,
+    angle_impulse: f32,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl WeldJoint {
+    /// Creating a rigid weld joint
+    ///
+    /// Anchors are given relative to the centers of the objects at zero angle, `reference_angle` is the angle of the second object relative to the first one that is kept
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        reference_angle: Angle,
+    ) -> Self {
+        WeldJoint {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            reference_angle: reference_angle.get_radian(),
+            frequency: 0.0,
+            damping_ratio: 0.0,
+            arm1: Vec2D::default(),
+            arm2: Vec2D::default(),
+            linear_gamma: 0.0,
+            linear_bias: Vec2D::default(),
+            angle_gamma: 0.0,
+            angle_bias: 0.0,
+            axial_mass: 0.0,
+            impulse: Vec2D::default(),
+            angle_impulse: 0.0,
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    pub fn get_reference_angle(&self) -> Angle {
+        Angle::new(self.reference_angle)
+    }
+
+    /// Frequency of the springs in hertz, zero makes the joint rigid
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn set_damping_ratio(&mut self, damping_ratio: f32) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    pub fn get_damping_ratio(&self) -> f32 {
+        self.damping_ratio
+    }
+
+    /// Returns the impulse that held the anchors together at the last step, applied to the second object
+    pub fn get_impulse(&self) -> Vec2D {
+        self.impulse
+    }
+
+    /// Returns the angular impulse that held the angle at the last step, applied to the second object
+    pub fn get_angle_impulse(&self) -> f32 {
+        self.angle_impulse
+    }
+}
+
+impl ConstraintInterface for WeldJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        self.arm1 = body1.get_arm(&self.local_anchor1, 0.0);
+        self.arm2 = body2.get_arm(&self.local_anchor2, 0.0);
+
+        // the springs turn the errors into velocities, a rigid joint corrects them in the position iterations
+        let inversion_mass = body1.inversion_mass + body2.inversion_mass;
+        let mass = if inversion_mass == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_mass
+        };
+        let (linear_gamma, linear_bias_rate) =
+            get_softness(mass, self.frequency, self.damping_ratio, time);
+        let error = body2.position + self.arm2 - body1.position - self.arm1;
+        self.linear_gamma = linear_gamma;
+        self.linear_bias = error.mul_n(linear_bias_rate);
+
+        let inversion_inertia = body1.inversion_inertia + body2.inversion_inertia;
+        let axial_mass = if inversion_inertia == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_inertia
+        };
+        let (angle_gamma, angle_bias_rate) =
+            get_softness(axial_mass, self.frequency, self.damping_ratio, time);
+        self.angle_gamma = angle_gamma;
+        self.angle_bias = get_relative_angle(body1, body2, self.reference_angle, 0.0) * angle_bias_rate;
+        self.axial_mass = if inversion_inertia + angle_gamma == 0.0 {
+            0.0
+        } else {
+            1.0 / (inversion_inertia + angle_gamma)
+        };
+
+        body1.apply_impulse(&self.impulse.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&self.impulse, &self.arm2);
+        body1.apply_angular_impulse(-self.angle_impulse);
+        body2.apply_angular_impulse(self.angle_impulse);
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        let angle_velocity = body2.angle_velocity - body1.angle_velocity;
+        let impulse = -self.axial_mass
+            * (angle_velocity + self.angle_bias + self.angle_gamma * self.angle_impulse);
+        self.angle_impulse += impulse;
+        body1.apply_angular_impulse(-impulse);
+        body2.apply_angular_impulse(impulse);
+
+        let relative_velocity = body2.velocity_at(&self.arm2) - body1.velocity_at(&self.arm1);
+        let impulse = get_point_impulse(
+            body1,
+            body2,
+            &self.arm1,
+            &self.arm2,
+            &(relative_velocity + self.linear_bias + self.impulse.mul_n(self.linear_gamma)),
+            self.linear_gamma,
+        );
+        self.impulse += impulse;
+        body1.apply_impulse(&impulse.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&impulse, &self.arm2);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // springs are allowed to bend
+        if self.frequency > 0.0 {
+            return;
+        }
+
+        // the errors are calculated for the positions at the end of the step
+        let error = get_relative_angle(body1, body2, self.reference_angle, self.time);
+        let impulse = -self.axial_mass * error * solver.get_correction_factor();
+        body1.apply_angular_displacement(-impulse);
+        body2.apply_angular_displacement(impulse);
+
+        let arm1 = body1.get_arm(&self.local_anchor1, self.time);
+        let arm2 = body2.get_arm(&self.local_anchor2, self.time);
+        let error = body2.get_position(self.time) + arm2 - body1.get_position(self.time) - arm1;
+        let correction = error
+            .unit()
+            .mul_n(solver.get_joint_correction(error.len_vector(&Vec2D::default())));
+
+        let impulse = get_point_impulse(body1, body2, &arm1, &arm2, &correction, 0.0);
+        body1.apply_displacement(&impulse.mul_n(-1.0), &arm1);
+        body2.apply_displacement(&impulse, &arm2);
+    }
+}
+
+impl JointInterface for WeldJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::physics_engine::{
+        solver::ObjectId,
+        test_utils::{map_with_gravity, square},
+        types::vec2d::Vec2D,
+    };
+
+    #[test]
+    fn weld_joint_holds_the_object_in_place() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(500.0, 100.0), 20.0, 1.0)));
+
+        // the box is glued by its corner, so the gravity also tries to turn it
+        let position = map.dyn_objects[0].get_current_position();
+        map.weld(ObjectId::Static(0), ObjectId::Dynamic(0), Vec2D::new(490.0, 90.0));
+
+        let mut max_error: f32 = 0.0;
+        let mut max_angle_error: f32 = 0.0;
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+
+            let dyn_object = &map.dyn_objects[0];
+            max_error = max_error.max(dyn_object.get_current_position().len_vector(&position));
+            max_angle_error = max_angle_error.max(dyn_object.get_angle().get_signed_radian().abs());
+        }
+        assert!(max_error < 0.1);
+        assert!(max_angle_error < 0.01);
+    }
+
+    #[test]
+    fn weld_joint_holds_overlapping_objects_together() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(500.0, 900.0), 40.0, 1.0)));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(520.0, 900.0), 10.0, 0.1)));
+
+        // the food is held in the jaws of the ant, so both fall together onto the floor
+        let (ant, food) = (ObjectId::Dynamic(0), ObjectId::Dynamic(1));
+        map.weld(ant, food, Vec2D::new(520.0, 900.0));
+        let local_food = map.get_local_point(ant, Vec2D::new(520.0, 900.0));
+
+        let mut max_error: f32 = 0.0;
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+
+            let food_position = map.dyn_objects[1].get_current_position();
+            max_error = max_error.max(map.get_local_point(ant, food_position).len_vector(&local_food));
+        }
+        assert!(max_error < 0.1);
+    }
+}
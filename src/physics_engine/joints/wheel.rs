@@ -0,0 +1,305 @@
+use super::{
+    super::{
+        solver::{ObjectId, Solver, SolverBody},
+        traits::{constraint_interface::ConstraintInterface, joint_interface::JointInterface},
+        types::vec2d::Vec2D,
+    },
+    get_axes, get_softness, JointAxis,
+};
+
+/// Joint of a wheel (the second object) on a suspension of the first object
+///
+/// The wheel turns freely and slides along the axis of the suspension, on which it is held by a spring. The turn can be driven by a motor
+pub struct WheelJoint {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    local_axis: Vec2D,
+    frequency: f32,
+    damping_ratio: f32,
+    enable_motor: bool,
+    motor_speed: f32,
+    max_motor_torque: f32,
+    axis: JointAxis,
+    perpendicular: JointAxis,
+    translation: f32,
+    axial_mass: f32,
+    soft_mass: f32,
+    gamma: f32,
+    bias: f32,
+    impulse: f32,
+    spring_impulse: f32,
+    motor_impulse: f32,
+    collide_connected: bool,
+    time: f32,
+}
+
+impl WheelJoint {
+    /// Creating a wheel joint
+    ///
+    /// Anchors and the axis of the suspension are given relative to the objects at zero angle, the spring keeps the anchors together
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        local_axis: Vec2D,
+    ) -> Self {
+        WheelJoint {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            local_axis: local_axis.unit(),
+            frequency: 0.0,
+            damping_ratio: 0.0,
+            enable_motor: false,
+            motor_speed: 0.0,
+            max_motor_torque: 0.0,
+            axis: JointAxis::default(),
+            perpendicular: JointAxis::default(),
+            translation: 0.0,
+            axial_mass: 0.0,
+            soft_mass: 0.0,
+            gamma: 0.0,
+            bias: 0.0,
+            impulse: 0.0,
+            spring_impulse: 0.0,
+            motor_impulse: 0.0,
+            collide_connected: false,
+            time: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    pub fn get_local_axis(&self) -> Vec2D {
+        self.local_axis
+    }
+
+    /// Frequency of the suspension spring in hertz, zero lets the wheel slide freely along the axis
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    pub fn set_damping_ratio(&mut self, damping_ratio: f32) {
+        self.damping_ratio = damping_ratio;
+    }
+
+    pub fn get_damping_ratio(&self) -> f32 {
+        self.damping_ratio
+    }
+
+    pub fn set_enable_motor(&mut self, enable_motor: bool) {
+        self.enable_motor = enable_motor;
+    }
+
+    pub fn get_enable_motor(&self) -> bool {
+        self.enable_motor
+    }
+
+    /// Angle velocity of the wheel relative to the first object that the motor tries to keep
+    pub fn set_motor_speed(&mut self, motor_speed: f32) {
+        self.motor_speed = motor_speed;
+    }
+
+    pub fn get_motor_speed(&self) -> f32 {
+        self.motor_speed
+    }
+
+    pub fn set_max_motor_torque(&mut self, max_motor_torque: f32) {
+        self.max_motor_torque = max_motor_torque;
+    }
+
+    pub fn get_max_motor_torque(&self) -> f32 {
+        self.max_motor_torque
+    }
+
+    /// Returns the compression of the suspension at the last step, the translation of the wheel along the axis
+    pub fn get_translation(&self) -> f32 {
+        self.translation
+    }
+
+    /// Returns the torque of the motor at the last step
+    pub fn get_motor_torque(&self) -> f32 {
+        if self.time == 0.0 {
+            0.0
+        } else {
+            self.motor_impulse / self.time
+        }
+    }
+}
+
+impl ConstraintInterface for WheelJoint {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        let (axis, perpendicular, distance) = get_axes(
+            body1,
+            body2,
+            &self.local_anchor1,
+            &self.local_anchor2,
+            &self.local_axis,
+            0.0,
+        );
+        self.axis = axis;
+        self.perpendicular = perpendicular;
+        self.translation = Vec2D::dot(&distance, &axis.direction);
+
+        let inversion_inertia = body1.inversion_inertia + body2.inversion_inertia;
+        self.axial_mass = if inversion_inertia == 0.0 {
+            0.0
+        } else {
+            1.0 / inversion_inertia
+        };
+
+        // the suspension spring turns the translation into a velocity
+        let (gamma, bias_rate) =
+            get_softness(self.axis.mass, self.frequency, self.damping_ratio, time);
+        self.gamma = gamma;
+        self.bias = self.translation * bias_rate;
+        self.soft_mass = if self.axis.mass == 0.0 || gamma == 0.0 {
+            0.0
+        } else {
+            1.0 / (1.0 / self.axis.mass + gamma)
+        };
+
+        if !self.enable_motor {
+            self.motor_impulse = 0.0;
+        }
+        if self.frequency <= 0.0 {
+            self.spring_impulse = 0.0;
+        }
+
+        self.axis.apply_impulse(body1, body2, self.spring_impulse);
+        self.perpendicular.apply_impulse(body1, body2, self.impulse);
+        body1.apply_angular_impulse(-self.motor_impulse);
+        body2.apply_angular_impulse(self.motor_impulse);
+        self.time = time;
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        if self.frequency > 0.0 {
+            let velocity = self.axis.get_velocity(body1, body2);
+            let impulse =
+                -self.soft_mass * (velocity + self.bias + self.gamma * self.spring_impulse);
+            self.spring_impulse += impulse;
+            self.axis.apply_impulse(body1, body2, impulse);
+        }
+
+        // the motor can't exceed its torque during the step
+        if self.enable_motor {
+            let angle_velocity = body2.angle_velocity - body1.angle_velocity - self.motor_speed;
+            let max_impulse = self.max_motor_torque * self.time;
+            let new_impulse =
+                (self.motor_impulse - self.axial_mass * angle_velocity).clamp(-max_impulse, max_impulse);
+            let impulse = new_impulse - self.motor_impulse;
+            self.motor_impulse = new_impulse;
+            body1.apply_angular_impulse(-impulse);
+            body2.apply_angular_impulse(impulse);
+        }
+
+        // keeps the wheel on the axis of the suspension
+        let impulse = -self.perpendicular.mass * self.perpendicular.get_velocity(body1, body2);
+        self.impulse += impulse;
+        self.perpendicular.apply_impulse(body1, body2, impulse);
+    }
+
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver) {
+        // the error is calculated for the positions at the end of the step
+        let (_, perpendicular, distance) = get_axes(
+            body1,
+            body2,
+            &self.local_anchor1,
+            &self.local_anchor2,
+            &self.local_axis,
+            self.time,
+        );
+        let error = Vec2D::dot(&distance, &perpendicular.direction);
+        let correction = solver.get_joint_correction(error.abs()) * error.signum();
+        perpendicular.apply_displacement(body1, body2, -perpendicular.mass * correction);
+    }
+}
+
+impl JointInterface for WheelJoint {
+    fn set_collide_connected(&mut self, collide_connected: bool) {
+        self.collide_connected = collide_connected;
+    }
+
+    fn get_collide_connected(&self) -> bool {
+        self.collide_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::physics_engine::{
+        joints::JointHandle,
+        map::Map,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that hangs a wheel at the point on a vertical suspension of the top border
+    fn hanging_wheel(gravity: Vec2D, point: Vec2D) -> (Map, JointHandle) {
+        let mut map = map_with_gravity(gravity);
+        map.dyn_objects.push(Box::new(square(point, 20.0, 1.0)));
+
+        let joint = WheelJoint::new(
+            ObjectId::Static(0),
+            ObjectId::Dynamic(0),
+            map.get_local_point(ObjectId::Static(0), point),
+            Vec2D::default(),
+            Vec2D::new(0.0, 1.0),
+        );
+        let handle = map.add_joint(Box::new(joint));
+
+        (map, handle)
+    }
+
+    #[test]
+    fn wheel_suspension_holds_the_weight() {
+        let (mut map, handle) = hanging_wheel(Vec2D::new(0.0, 100.0), Vec2D::new(500.0, 100.0));
+        let joint = map.get_mut_joint::<WheelJoint>(handle).unwrap();
+        joint.set_frequency(2.0);
+        joint.set_damping_ratio(1.0);
+        for _ in 0..180 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the spring is compressed by the weight divided by its stiffness
+        let compression = 100.0 / (2.0 * PI * 2.0).powi(2);
+        let translation = map.get_joint::<WheelJoint>(handle).unwrap().get_translation();
+        assert!((translation - compression).abs() < 0.05);
+        assert!((map.dyn_objects[0].get_current_position().x - 500.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn wheel_motor_turns_the_wheel() {
+        let (mut map, handle) = hanging_wheel(Vec2D::default(), Vec2D::new(500.0, 100.0));
+        let joint = map.get_mut_joint::<WheelJoint>(handle).unwrap();
+        joint.set_enable_motor(true);
+        joint.set_motor_speed(5.0);
+        joint.set_max_motor_torque(1000.0);
+        for _ in 0..60 {
+            map.run(1.0 / 60.0);
+        }
+
+        assert!((map.dyn_objects[0].get_angle_velocity() - 5.0).abs() < 0.01);
+    }
+}
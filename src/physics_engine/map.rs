@@ -1,14 +1,62 @@
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    f32::consts::PI,
+    thread,
+};
+
 use super::{
     objects::{line::Line, rectangle::Rectangle},
-    traits::{move_interface::MoveInterface, object_interface::ObjectInterface},
-    types::{vec2d::Vec2D, angle::Angle},
-    collision::Collision,
+    traits::{
+        constraint_interface::ConstraintInterface, force_field_interface::ForceFieldInterface,
+        joint_interface::JointInterface, move_interface::MoveInterface,
+        object_interface::ObjectInterface,
+    },
+    types::{vec2d::Vec2D, angle::Angle, matrix2d::Matrix2D},
+    collision::{Collision, ContactEvent},
+    integrator::{Integrator, Motion},
+    island::Island,
+    joints::{weld::WeldJoint, JointHandle},
+    spring::Spring,
+    fluid::FluidRegion,
+    wind::Wind,
+    soft_body::SoftBody,
+    particles::ParticleSystem,
+    pbd::PbdBody,
+    query::shape_cast,
+    solver::{ObjectId, Solver, SolverBody},
 };
 
 /// Structure describing the map on which objects exist and interact
 pub struct Map {
     objects: Vec<Box<dyn ObjectInterface>>,
     pub dyn_objects: Vec<Box<dyn MoveInterface>>,
+    pub force_fields: Vec<Box<dyn ForceFieldInterface>>,
+    pub springs: Vec<Spring>,
+    pub fluids: Vec<FluidRegion>,
+    pub winds: Vec<Wind>,
+    pub soft_bodies: Vec<SoftBody>,
+    pub particle_systems: Vec<ParticleSystem>,
+    pub pbd_bodies: Vec<PbdBody>,
+    gravity: Vec2D,
+    air_density: f32,
+    integrator: Integrator,
+    solver: Solver,
+    collisions: Vec<Collision>,
+    contact_events: Vec<ContactEvent>,
+    substeps: usize,
+    fixed_time: f32,
+    max_fixed_steps: usize,
+    accumulated_time: f32,
+    previous_transforms: Vec<(Vec2D, Angle)>,
+    previous_outlines: Vec<Vec<Vec2D>>,
+    islands: Vec<Island>,
+    sleep_velocity: f32,
+    sleep_angle_velocity: f32,
+    time_to_sleep: f32,
+    threads: usize,
+    joints: BTreeMap<JointHandle, Box<dyn JointInterface>>,
+    next_joint: usize,
 }
 
 impl Map {
@@ -17,6 +65,32 @@ impl Map {
         Map {
             objects: Vec::<Box<dyn ObjectInterface>>::new(),
             dyn_objects: Vec::<Box<dyn MoveInterface>>::new(),
+            force_fields: Vec::<Box<dyn ForceFieldInterface>>::new(),
+            springs: Vec::<Spring>::new(),
+            fluids: Vec::<FluidRegion>::new(),
+            winds: Vec::<Wind>::new(),
+            soft_bodies: Vec::<SoftBody>::new(),
+            particle_systems: Vec::<ParticleSystem>::new(),
+            pbd_bodies: Vec::<PbdBody>::new(),
+            gravity: Vec2D::default(),
+            air_density: 1.0,
+            integrator: Integrator::default(),
+            solver: Solver::default(),
+            collisions: Vec::<Collision>::new(),
+            contact_events: Vec::<ContactEvent>::new(),
+            substeps: 1,
+            fixed_time: 1.0 / 60.0,
+            max_fixed_steps: 8,
+            accumulated_time: 0.0,
+            previous_transforms: Vec::<(Vec2D, Angle)>::new(),
+            previous_outlines: Vec::<Vec<Vec2D>>::new(),
+            islands: Vec::<Island>::new(),
+            sleep_velocity: 2.0,
+            sleep_angle_velocity: 0.05,
+            time_to_sleep: 0.5,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            joints: BTreeMap::<JointHandle, Box<dyn JointInterface>>::new(),
+            next_joint: 0,
         }
     }
 
@@ -28,7 +102,7 @@ impl Map {
             0.0,
             1.0,
             Vec2D::default(),
-            0.0,
+            0.5,
             0.0,
             0.0,
         )));
@@ -38,7 +112,7 @@ impl Map {
             0.0,
             1.0,
             Vec2D::default(),
-            0.0,
+            0.5,
             0.0,
             0.0,
         )));
@@ -48,7 +122,7 @@ impl Map {
             0.0,
             1.0,
             Vec2D::default(),
-            0.0,
+            0.5,
             0.0,
             0.0,
         )));
@@ -58,7 +132,7 @@ impl Map {
             0.0,
             1.0,
             Vec2D::default(),
-            0.0,
+            0.5,
             0.0,
             0.0,
         )));
@@ -78,66 +152,787 @@ impl Map {
         )));
     }
 
+    /// Adds a non-moving object, e.g. a conveyor line, and returns its id
+    pub fn add_object(&mut self, object: Box<dyn ObjectInterface>) -> ObjectId {
+        self.objects.push(object);
+        ObjectId::Static(self.objects.len() - 1)
+    }
+
+    /// Returns the non-moving object by its index for changing it, the objects touching it are woken
+    pub fn get_mut_object(&mut self, j: usize) -> Option<&mut dyn ObjectInterface> {
+        let object = self.objects.get_mut(j)?;
+
+        // sleeping objects don't take part in the step, so the contacts with them are detected here
+        for (i, dyn_object) in self.dyn_objects.iter_mut().enumerate() {
+            if dyn_object.is_sleeping()
+                && !Collision::detect(
+                    (ObjectId::Dynamic(i), dyn_object.as_ref()),
+                    (ObjectId::Static(j), object.as_ref()),
+                )
+                .is_empty()
+            {
+                dyn_object.wake();
+            }
+        }
+
+        Some(object.as_mut())
+    }
+
     /// Start movement of objects belonging to this map
+    ///
+    /// The time is split into substeps, each of them moves objects and resolves collisions, contact events are gathered for the whole run.
+    /// A non-positive time leaves the map unchanged
     pub fn run(&mut self, time: f32) {
+        if time <= 0.0 {
+            return;
+        }
+
+        let mut contact_events = BTreeMap::<(ObjectId, ObjectId), ContactEvent>::new();
+
+        for _ in 0..self.substeps {
+            self.run_substep(time / self.substeps as f32);
+
+            for collision in &self.collisions {
+                let event = contact_events.get(&collision.get_objects()).copied();
+                contact_events.insert(collision.get_objects(), collision.to_event(event));
+            }
+        }
+
+        // forces of the user act during the whole run, so they are removed only after all substeps
+        for dyn_object in self.dyn_objects.iter_mut() {
+            dyn_object.clear_forces();
+        }
+
+        self.contact_events = contact_events.into_values().collect();
+    }
+
+    /// Returns the contacts of objects found during the last run, one for every pair of objects
+    pub fn get_contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    pub fn set_substeps(&mut self, substeps: usize) {
+        self.substeps = substeps.max(1);
+    }
+
+    pub fn get_substeps(&self) -> usize {
+        self.substeps
+    }
+
+    /// Auxiliary function that moves objects and resolves their collisions once
+    fn run_substep(&mut self, time: f32) {
+        // constraints divide by the time, so an empty step is skipped
+        if time <= 0.0 {
+            return;
+        }
+
         let mut collisions = Vec::<Collision>::new();
 
-        // changes the potential characteristics of objects
-        for i in 0..self.dyn_objects.len() {
-            (*self.dyn_objects[i]).tracer(time);
+        // objects woken since the last step wake their islands
+        self.wake_islands();
+
+        // forces of the map are added to the forces of the user only for this substep
+        let mut map_forces = vec![(Vec2D::default(), 0.0); self.dyn_objects.len()];
+
+        // explicit springs of moving objects add their forces before the integration
+        let mut spring_forces = Vec::<(ObjectId, Vec2D, Vec2D)>::new();
+        for spring in self.springs.iter().filter(|spring| !spring.get_implicit()) {
+            let (object1, object2) = spring.get_objects();
+            if !self.is_awake(object1) && !self.is_awake(object2) {
+                continue;
+            }
+
+            let (force, point1, point2) =
+                spring.get_force(&self.get_body(object1), &self.get_body(object2));
+            spring_forces.push((object1, force.mul_n(-1.0), point1));
+            spring_forces.push((object2, force, point2));
         }
+        for (object, force, point) in spring_forces {
+            if let ObjectId::Dynamic(i) = object {
+                // springs are a part of the map, so unlike the forces of the user they don't keep objects awake
+                let sleep_time = self.dyn_objects[i].get_sleep_time();
+                self.dyn_objects[i].wake();
+                self.dyn_objects[i].set_sleep_time(sleep_time);
+
+                let arm = point - self.dyn_objects[i].get_current_position();
+                map_forces[i].0 += force;
+                map_forces[i].1 += Vec2D::cross(&arm, &force);
+            }
+        }
+
+        // fluids push up and slow down the objects in them
+        for (i, dyn_object) in self.dyn_objects.iter().enumerate() {
+            if dyn_object.is_sleeping() {
+                continue;
+            }
+
+            for fluid in &self.fluids {
+                if let Some((force, point, torque)) = fluid.get_force(&**dyn_object, self.gravity) {
+                    let arm = point - dyn_object.get_current_position();
+                    map_forces[i].0 += force;
+                    map_forces[i].1 += Vec2D::cross(&arm, &force) + torque;
+                }
+            }
+        }
+
+        // integrates the movement of objects by the accumulated forces, gravity, force fields and drag
+        let gravity = self.gravity;
+        let force_fields = &self.force_fields;
+        let winds = &self.winds;
+        let air_density = self.air_density;
+        let integrator = self.integrator;
+        let integrate = |(dyn_object, (map_force, map_torque)): &mut (&mut Box<dyn MoveInterface>, (Vec2D, f32))| {
+            let inversion_mass = dyn_object.get_inversion_mass();
+            let inversion_inertia = dyn_object.get_inversion_inertia();
+            let force = dyn_object.get_force() + *map_force;
+            let torque = dyn_object.get_torque() + *map_torque;
+            let drag = dyn_object.get_drag();
+            let shape: &dyn MoveInterface = &***dyn_object;
+
+            // objects without mass are not affected by gravity, force fields and the air
+            let acceleration = |motion: &Motion| {
+                if inversion_mass == 0.0 {
+                    return (Vec2D::default(), torque * inversion_inertia);
+                }
+
+                let field_acceleration = force_fields.iter().fold(gravity, |acceleration, force_field| {
+                    acceleration + force_field.get_acceleration_at(&motion.position)
+                });
+                (
+                    field_acceleration
+                        + Map::get_drag_acceleration(shape, motion, drag * air_density, winds, time)
+                        + force.mul_n(inversion_mass),
+                    torque * inversion_inertia,
+                )
+            };
+            // damping is per second, so it doesn't depend on the length of the step
+            let velocity = dyn_object
+                .get_velocity()
+                .mul_n((-dyn_object.get_linear_damping() * time).exp());
+            let angle_velocity =
+                dyn_object.get_angle_velocity() * (-dyn_object.get_angle_friction() * time).exp();
+            let motion = integrator.integrate(
+                &Motion::new(
+                    dyn_object.get_current_position(),
+                    dyn_object.get_angle().get_radian(),
+                    velocity,
+                    angle_velocity,
+                ),
+                time,
+                acceleration,
+            );
 
-        // creates an array of collisions with non-moving objects, if any
+            // objects are moved with the new velocities later, so only the difference of the scheme is applied now
+            dyn_object.set_current_position(motion.position - motion.velocity.mul_n(time));
+            dyn_object.set_angle(Angle::new(motion.angle - motion.angle_velocity * time));
+            dyn_object.set_velocity(motion.velocity);
+            dyn_object.set_angle_velocity(motion.angle_velocity);
+
+            // changes the potential characteristics of the object
+            dyn_object.tracer(time);
+        };
+
+        // moving objects don't affect each other during the integration, so they are split between the threads
+        let mut awake_objects: Vec<_> = self
+            .dyn_objects
+            .iter_mut()
+            .zip(map_forces)
+            .filter(|(dyn_object, _)| !dyn_object.is_sleeping())
+            .collect();
+        Map::for_each_parallel(&mut awake_objects, self.threads, integrate);
+
+        // sleeping objects touched by moving ones are woken together with their islands
+        let mut woken = false;
         for i in 0..self.dyn_objects.len() {
-            for j in 0..self.objects.len() {
-                // checks circumscribed circles for collision
-                if (*self.dyn_objects[i]).intersection_circumscribed_circles(&(*self.objects[j])) {
-                    // checks for collision using the sat method
-                    if let Some((min_overlap, smallest_axis, contact_vertex)) = (*self.dyn_objects[i]).sat(&(*self.objects[j])) {
-                        let mut collision = Collision::new(self.dyn_objects[i].as_mut_object(), self.objects[j].as_mut_object(), min_overlap, smallest_axis, contact_vertex);
-                        collision.divide_objects();
-                        collision.change_energy()
-                    }
+            for k in 0..self.dyn_objects.len() {
+                if self.dyn_objects[i].is_sleeping() || !self.dyn_objects[k].is_sleeping() {
+                    continue;
+                }
+
+                if self.dyn_objects[i].intersection_circumscribed_circles(self.dyn_objects[k].as_object())
+                    && self.dyn_objects[i].sat(self.dyn_objects[k].as_object()).is_some()
+                {
+                    self.dyn_objects[k].wake();
+                    woken = true;
                 }
             }
         }
 
-        // TODO: Either delete the array, or increase the scope of Collision
-        // resolves collision
-        while let Some(mut collision) = collisions.pop() {
-            collision.divide_objects();
-            collision.change_energy();
+        // joints and springs added since the last step wake the objects connected to moving ones
+        let connections = self
+            .joints
+            .values()
+            .map(|joint| joint.get_objects())
+            .chain(self.springs.iter().map(|spring| spring.get_objects()));
+        for connection in connections {
+            if let (ObjectId::Dynamic(i), ObjectId::Dynamic(k)) = connection {
+                if self.dyn_objects[i].is_sleeping() != self.dyn_objects[k].is_sleeping() {
+                    self.dyn_objects[i].wake();
+                    self.dyn_objects[k].wake();
+                    woken = true;
+                }
+            }
+        }
+        if woken {
+            self.wake_islands();
         }
 
-        // creates an array of collisions with moving objects, if any
-        for i in 0..self.dyn_objects.len() {
-            let (l_dyn_objects, r_dyn_objects) = self.dyn_objects.split_at_mut(i + 1);
-            for r_dyn_object in r_dyn_objects.iter_mut() {
-                // checks circumscribed circles for collision
-                if l_dyn_objects[i].intersection_circumscribed_circles(r_dyn_object.as_object()) {
-                    // checks for collision using the sat method
-                    if let Some((min_overlap, smallest_axis, contact_vertex)) = l_dyn_objects[i].sat(r_dyn_object.as_object()) {
-                        let mut collision = Collision::new(l_dyn_objects[i].as_mut_object(), r_dyn_object.as_mut_object(), min_overlap, smallest_axis, contact_vertex);
-                        collision.divide_objects();
-                        collision.change_energy()
+        // sleeping objects don't take part in the step
+        let awake: Vec<usize> = (0..self.dyn_objects.len())
+            .filter(|i| !self.dyn_objects[*i].is_sleeping())
+            .collect();
+
+        // objects connected by joints that don't let them collide are skipped, so that joints can hold them close
+        let jointed: HashSet<(ObjectId, ObjectId)> = self
+            .joints
+            .values()
+            .filter(|joint| !joint.get_collide_connected())
+            .map(|joint| {
+                let (object1, object2) = joint.get_objects();
+                (object1.min(object2), object1.max(object2))
+            })
+            .collect();
+
+        // pairs of moving objects with non-moving and other moving objects that can collide
+        let mut pairs = Vec::<(usize, ObjectId)>::new();
+        for (n, &i) in awake.iter().enumerate() {
+            let dyn_object = ObjectId::Dynamic(i);
+            let objects = (0..self.objects.len())
+                .map(ObjectId::Static)
+                .chain(awake[(n + 1)..].iter().map(|k| ObjectId::Dynamic(*k)));
+            for object in objects {
+                if !jointed.contains(&(dyn_object.min(object), dyn_object.max(object))) {
+                    pairs.push((i, object));
+                }
+            }
+        }
+
+        // creates an array of collisions, the pairs are checked independently of each other, so they are split between the threads
+        let objects = &self.objects;
+        let dyn_objects = &self.dyn_objects;
+        collisions.extend(Map::flat_map_parallel(&pairs, self.threads, |(i, object)| {
+            let object2: &dyn ObjectInterface = match *object {
+                ObjectId::Dynamic(k) => dyn_objects[k].as_object(),
+                ObjectId::Static(j) => &*objects[j],
+                ObjectId::World => return Vec::new(),
+            };
+            Collision::detect((ObjectId::Dynamic(*i), &*dyn_objects[*i]), (*object, object2))
+        }));
+
+        // continues the impulses of the collisions found in the previous step
+        let mut previous_collisions = HashMap::<(ObjectId, ObjectId), Vec<&Collision>>::new();
+        for collision in &self.collisions {
+            previous_collisions
+                .entry(collision.get_objects())
+                .or_default()
+                .push(collision);
+        }
+        for collision in collisions.iter_mut() {
+            if let Some(previous) = previous_collisions.get(&collision.get_objects()) {
+                for previous in previous {
+                    if collision.warm_start(previous) {
+                        break;
                     }
                 }
             }
         }
 
-        // changes the potential characteristics of objects
-        while let Some(mut collision) = collisions.pop() {
-            collision.divide_objects();
-            collision.change_energy();
+        // joints and implicit springs of moving objects are resolved in the same iterations as the collisions
+        let dyn_objects = &self.dyn_objects;
+        let is_active = |(object1, object2): (ObjectId, ObjectId)| {
+            [object1, object2].iter().any(|object| {
+                matches!(object, ObjectId::Dynamic(i) if !dyn_objects[*i].is_sleeping())
+            })
+        };
+        let spring_connections: Vec<(ObjectId, ObjectId)> = self
+            .springs
+            .iter()
+            .filter(|spring| !spring.get_implicit())
+            .map(|spring| spring.get_objects())
+            .filter(|objects| is_active(*objects))
+            .collect();
+        let constraints: Vec<&mut dyn ConstraintInterface> = self
+            .joints
+            .values_mut()
+            .filter(|joint| is_active(joint.get_objects()))
+            .map(|joint| &mut **joint as &mut dyn ConstraintInterface)
+            .chain(
+                self.springs
+                    .iter_mut()
+                    .filter(|spring| spring.get_implicit() && is_active(spring.get_objects()))
+                    .map(|spring| spring as &mut dyn ConstraintInterface),
+            )
+            .chain(
+                collisions
+                    .iter_mut()
+                    .map(|collision| collision as &mut dyn ConstraintInterface),
+            )
+            .collect();
+
+        // objects connected by constraints or explicit springs are resolved together, islands are resolved independently of each other
+        let mut connections: Vec<(ObjectId, ObjectId)> = constraints
+            .iter()
+            .map(|constraint| constraint.get_objects())
+            .collect();
+        connections.extend(spring_connections);
+        let islands = Island::build(&awake, &connections);
+        let mut island_bodies: Vec<Vec<SolverBody>> = islands
+            .iter()
+            .map(|island| {
+                island
+                    .objects
+                    .iter()
+                    .map(|i| SolverBody::new(dyn_objects[*i].as_object()))
+                    .collect()
+            })
+            .collect();
+        let static_bodies: Vec<SolverBody> = self
+            .objects
+            .iter()
+            .map(|object| SolverBody::new_static(&(**object)))
+            .collect();
+
+        // explicit springs only join islands, constraints without an island connect no moving object and are skipped
+        let count = constraints.len();
+        let mut constraint_islands = vec![None; count];
+        for (n, island) in islands.iter().enumerate() {
+            for j in island.constraints.iter().filter(|j| **j < count) {
+                constraint_islands[*j] = Some(n);
+            }
+        }
+        let mut island_constraints: Vec<Vec<&mut dyn ConstraintInterface>> =
+            islands.iter().map(|_| Vec::new()).collect();
+        for (constraint, n) in constraints.into_iter().zip(constraint_islands) {
+            if let Some(n) = n {
+                island_constraints[n].push(constraint);
+            }
+        }
+
+        self.solver.solve_islands(
+            &islands,
+            &mut island_bodies,
+            &static_bodies,
+            &mut island_constraints,
+            time,
+            self.threads,
+        );
+
+        for (island, bodies) in islands.iter().zip(island_bodies.iter()) {
+            for (i, body) in island.objects.iter().zip(bodies.iter()) {
+                body.apply_to(self.dyn_objects[*i].as_mut_object());
+            }
         }
 
         // assigns potential characteristics to present characteristics
-        for i in 0..self.dyn_objects.len() {
+        for &i in &awake {
             (*self.dyn_objects[i]).run(time);
         }
+
+        // soft bodies move after the rigid objects and push them with their hulls
+        for soft_body in self.soft_bodies.iter_mut() {
+            soft_body.run(time, self.gravity, &self.force_fields, &self.objects, &mut self.dyn_objects);
+        }
+
+        // cloth and ropes are pushed by objects, but don't push them
+        for pbd_body in self.pbd_bodies.iter_mut() {
+            pbd_body.run(time, self.gravity, &self.force_fields, &self.objects, &self.dyn_objects);
+        }
+
+        for particle_system in self.particle_systems.iter_mut() {
+            particle_system.run(time, self.gravity, &self.force_fields, &self.objects);
+        }
+
+        self.collisions = collisions;
+        self.sleep_islands(islands, time);
     }
 
-    pub fn create_data(&self) -> (Vec<Vec2D>, Vec<Angle>) {
+    /// Auxiliary function that calls the function for every item, the items are split evenly between the threads
+    fn for_each_parallel<T: Send, F: Fn(&mut T) + Sync>(items: &mut [T], threads: usize, function: F) {
+        let chunk = items.len().div_ceil(threads.max(1)).max(1);
+
+        // starting threads costs more than handling a single group of items
+        if chunk >= items.len() {
+            items.iter_mut().for_each(function);
+            return;
+        }
+
+        thread::scope(|scope| {
+            for items in items.chunks_mut(chunk) {
+                let function = &function;
+                scope.spawn(move || items.iter_mut().for_each(function));
+            }
+        });
+    }
+
+    /// Auxiliary function that returns the results of the function for every item in the order of the items, the items are split evenly between the threads
+    fn flat_map_parallel<T: Sync, R: Send, F: Fn(&T) -> Vec<R> + Sync>(
+        items: &[T],
+        threads: usize,
+        function: F,
+    ) -> Vec<R> {
+        let chunk = items.len().div_ceil(threads.max(1)).max(1);
+
+        // starting threads costs more than handling a single group of items
+        if chunk >= items.len() {
+            return items.iter().flat_map(function).collect();
+        }
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = items
+                .chunks(chunk)
+                .map(|items| {
+                    let function = &function;
+                    scope.spawn(move || items.iter().flat_map(function).collect::<Vec<R>>())
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// Auxiliary function that wakes every object of the islands in which at least one object is awake
+    fn wake_islands(&mut self) {
+        let dyn_objects = &mut self.dyn_objects;
+
+        // objects removed from the map break their islands
+        self.islands
+            .retain(|island| island.objects.iter().all(|i| *i < dyn_objects.len()));
+
+        for island in &self.islands {
+            if island.objects.iter().any(|i| !dyn_objects[*i].is_sleeping()) {
+                for i in &island.objects {
+                    if dyn_objects[*i].is_sleeping() {
+                        dyn_objects[*i].wake();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Auxiliary function that puts to sleep the islands whose objects have been slow for long enough
+    ///
+    /// The new islands replace the islands of the awake objects, the islands of sleeping objects are kept to wake them together
+    fn sleep_islands(&mut self, islands: Vec<Island>, time: f32) {
+        let dyn_objects = &mut self.dyn_objects;
+        self.islands
+            .retain(|island| island.objects.iter().all(|i| dyn_objects[*i].is_sleeping()));
+
+        for island in islands {
+            let mut min_sleep_time = f32::INFINITY;
+            for i in &island.objects {
+                let dyn_object = &mut dyn_objects[*i];
+                if dyn_object.get_velocity().len_vector(&Vec2D::default()) < self.sleep_velocity
+                    && dyn_object.get_angle_velocity().abs() < self.sleep_angle_velocity
+                {
+                    dyn_object.set_sleep_time(dyn_object.get_sleep_time() + time);
+                } else {
+                    dyn_object.set_sleep_time(0.0);
+                }
+                min_sleep_time = min_sleep_time.min(dyn_object.get_sleep_time());
+            }
+
+            // the whole island falls asleep at once, so that a sleeping object doesn't hold up a moving one
+            if min_sleep_time >= self.time_to_sleep {
+                for i in &island.objects {
+                    dyn_objects[*i].set_sleeping(true);
+                    dyn_objects[*i].set_velocity(Vec2D::default());
+                    dyn_objects[*i].set_angle_velocity(0.0);
+                }
+            }
+
+            self.islands.push(island);
+        }
+    }
+
+    /// Linear velocity below which objects are considered resting
+    pub fn set_sleep_velocity(&mut self, sleep_velocity: f32) {
+        self.sleep_velocity = sleep_velocity;
+    }
+
+    pub fn get_sleep_velocity(&self) -> f32 {
+        self.sleep_velocity
+    }
+
+    /// Angle velocity below which objects are considered resting
+    pub fn set_sleep_angle_velocity(&mut self, sleep_angle_velocity: f32) {
+        self.sleep_angle_velocity = sleep_angle_velocity;
+    }
+
+    pub fn get_sleep_angle_velocity(&self) -> f32 {
+        self.sleep_angle_velocity
+    }
+
+    /// Time during which an island has to rest before it falls asleep, `f32::INFINITY` disables sleeping
+    pub fn set_time_to_sleep(&mut self, time_to_sleep: f32) {
+        self.time_to_sleep = time_to_sleep;
+    }
+
+    pub fn get_time_to_sleep(&self) -> f32 {
+        self.time_to_sleep
+    }
+
+    /// Number of threads that move objects, detect their collisions and resolve the islands of a step, the result doesn't depend on it
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn get_threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Adds the joint to the map, the returned handle stays valid until the joint is removed
+    pub fn add_joint(&mut self, joint: Box<dyn JointInterface>) -> JointHandle {
+        let (object1, object2) = joint.get_objects();
+        self.wake_object(object1);
+        self.wake_object(object2);
+
+        let handle = JointHandle(self.next_joint);
+        self.next_joint += 1;
+        self.joints.insert(handle, joint);
+
+        handle
+    }
+
+    /// Removes the joint from the map, the objects it connected are woken
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Box<dyn JointInterface>> {
+        let joint = self.joints.remove(&handle)?;
+        let (object1, object2) = joint.get_objects();
+        self.wake_object(object1);
+        self.wake_object(object2);
+
+        Some(joint)
+    }
+
+    /// Returns the joint by its handle, if the joint exists and has the requested type
+    pub fn get_joint<T: JointInterface>(&self, handle: JointHandle) -> Option<&T> {
+        let joint: &dyn Any = &**self.joints.get(&handle)?;
+        joint.downcast_ref::<T>()
+    }
+
+    /// Returns the joint by its handle for changing it, the objects it connects are woken
+    pub fn get_mut_joint<T: JointInterface>(&mut self, handle: JointHandle) -> Option<&mut T> {
+        let (object1, object2) = self.joints.get(&handle)?.get_objects();
+        self.wake_object(object1);
+        self.wake_object(object2);
+
+        let joint: &mut dyn Any = &mut **self.joints.get_mut(&handle)?;
+        joint.downcast_mut::<T>()
+    }
+
+    /// Returns the handles of all joints in the order of their creation
+    pub fn get_joint_handles(&self) -> Vec<JointHandle> {
+        self.joints.keys().copied().collect()
+    }
+
+    /// Casts the convex polygon moved by the displacement against non-moving and moving objects of the map
+    ///
+    /// Returns the first hit as `query::shape_cast` does, together with the id of the hit object
+    pub fn shape_cast(&self, polygon: &[Vec2D], displacement: Vec2D) -> Option<(f32, Vec2D, Vec2D, ObjectId)> {
+        let objects = self
+            .objects
+            .iter()
+            .enumerate()
+            .map(|(j, object)| (ObjectId::Static(j), object.as_ref()));
+        let dyn_objects = self
+            .dyn_objects
+            .iter()
+            .enumerate()
+            .map(|(i, dyn_object)| (ObjectId::Dynamic(i), dyn_object.as_object()));
+
+        objects
+            .chain(dyn_objects)
+            .filter_map(|(id, object)| {
+                shape_cast(polygon, &displacement, object).map(|(fraction, point, normal)| (fraction, point, normal, id))
+            })
+            .min_by(|hit1, hit2| hit1.0.total_cmp(&hit2.0))
+    }
+
+    /// Converts the point of the map to the point relative to the object at zero angle, which is used for the anchors of joints
+    pub fn get_local_point(&self, object: ObjectId, point: Vec2D) -> Vec2D {
+        let body = self.get_body(object);
+
+        Matrix2D::rotation_matrix(&Angle::new(-body.angle)).multiply_vec2d(&(point - body.position))
+    }
+
+    /// Returns the angle of the second object relative to the first one, which is used as the reference angle of joints
+    pub fn get_relative_angle(&self, object1: ObjectId, object2: ObjectId) -> Angle {
+        Angle::new(self.get_body(object2).angle - self.get_body(object1).angle)
+    }
+
+    /// Glues two objects together at the point of the map, keeping their current relative position and angle
+    pub fn weld(&mut self, object1: ObjectId, object2: ObjectId, point: Vec2D) -> JointHandle {
+        let mut joint = WeldJoint::new(
+            object1,
+            object2,
+            self.get_local_point(object1, point),
+            self.get_local_point(object2, point),
+            self.get_relative_angle(object1, object2),
+        );
+        // the food is usually held inside the jaws, so the welded objects must not push each other apart
+        joint.set_collide_connected(false);
+
+        self.add_joint(Box::new(joint))
+    }
+
+    /// Auxiliary function for calculating the acceleration given by the air to the object with the motion
+    ///
+    /// The drag grows with the square of the velocity relative to the wind and with the width of the shape across the flow
+    fn get_drag_acceleration(
+        shape: &dyn MoveInterface,
+        motion: &Motion,
+        drag: f32,
+        winds: &[Wind],
+        time: f32,
+    ) -> Vec2D {
+        let air_velocity = winds.iter().fold(Vec2D::default(), |velocity, wind| {
+            velocity + wind.get_velocity_at(&motion.position)
+        });
+        let relative_velocity = motion.velocity - air_velocity;
+        let speed = relative_velocity.len_vector(&Vec2D::default());
+        if drag == 0.0 || speed == 0.0 {
+            return Vec2D::default();
+        }
+
+        // the projected area is the length of the projection of the vertices on the normal of the flow,
+        // the vertices are taken at the start of the step, so the normal is turned back by the turn of the motion
+        let flow = relative_velocity.unit();
+        let turn = Angle::new(shape.get_angle().get_radian() - motion.angle);
+        let axis = Matrix2D::rotation_matrix(&turn).multiply_vec2d(&flow.normal());
+        let (max, min, _) = shape.projection_on_axis(&axis);
+        let acceleration = 0.5 * drag * (max - min) * speed * speed * shape.get_inversion_mass();
+
+        // the drag can't turn the object against the flow during the step, otherwise light objects would shake
+        flow.mul_n(-acceleration.min(speed / time))
+    }
+
+    /// Auxiliary function that returns the current characteristics of the object referenced by the object id
+    fn get_body(&self, object: ObjectId) -> SolverBody {
+        match object {
+            ObjectId::Static(j) => SolverBody::new_static(&*self.objects[j]),
+            ObjectId::Dynamic(i) => SolverBody::new(self.dyn_objects[i].as_object()),
+            ObjectId::World => SolverBody::new_world(),
+        }
+    }
+
+    /// Auxiliary function that checks if the object moves in the step
+    fn is_awake(&self, object: ObjectId) -> bool {
+        matches!(object, ObjectId::Dynamic(i) if !self.dyn_objects[i].is_sleeping())
+    }
+
+    /// Auxiliary function that wakes the object, if it can move
+    fn wake_object(&mut self, object: ObjectId) {
+        if let ObjectId::Dynamic(i) = object {
+            if let Some(dyn_object) = self.dyn_objects.get_mut(i) {
+                dyn_object.wake();
+            }
+        }
+    }
+
+    /// Returns the islands of the last step, sleeping islands keep the objects they had when they fell asleep
+    pub fn get_islands(&self) -> &[Island] {
+        &self.islands
+    }
+
+    /// Accumulates the passed time and moves objects by steps of fixed time, so that the simulation is reproducible
+    ///
+    /// Returns the number of steps taken, which is limited by the max number of fixed steps, the time that doesn't fit is dropped
+    pub fn step(&mut self, time: f32) -> usize {
+        self.accumulated_time += time;
+
+        let mut steps = 0;
+        while self.accumulated_time >= self.fixed_time && steps < self.max_fixed_steps {
+            // remembers the characteristics before the step for interpolation
+            self.previous_transforms = self
+                .dyn_objects
+                .iter()
+                .map(|dyn_object| (dyn_object.get_current_position(), dyn_object.get_angle()))
+                .collect();
+            self.previous_outlines = self
+                .soft_bodies
+                .iter()
+                .map(|soft_body| soft_body.get_outline())
+                .collect();
+
+            self.run(self.fixed_time);
+            self.accumulated_time -= self.fixed_time;
+            steps += 1;
+        }
+
+        // the whole steps that didn't fit are dropped, so that the simulation doesn't fall behind more and more
+        if self.accumulated_time >= self.fixed_time {
+            self.accumulated_time %= self.fixed_time;
+        }
+
+        steps
+    }
+
+    /// Returns the part of the fixed step that has passed since the last step, used for interpolation
+    pub fn get_alpha(&self) -> f32 {
+        (self.accumulated_time / self.fixed_time).clamp(0.0, 1.0)
+    }
+
+    /// Time of one fixed step, non-positive times are ignored
+    pub fn set_fixed_time(&mut self, fixed_time: f32) {
+        if fixed_time > 0.0 {
+            self.fixed_time = fixed_time;
+        }
+    }
+
+    pub fn get_fixed_time(&self) -> f32 {
+        self.fixed_time
+    }
+
+    /// Max number of fixed steps taken by one call of step, at least one
+    pub fn set_max_fixed_steps(&mut self, max_fixed_steps: usize) {
+        self.max_fixed_steps = max_fixed_steps.max(1);
+    }
+
+    pub fn get_max_fixed_steps(&self) -> usize {
+        self.max_fixed_steps
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec2D) {
+        self.gravity = gravity;
+    }
+
+    pub fn get_gravity(&self) -> Vec2D {
+        self.gravity
+    }
+
+    /// Density of the air, which scales the drag of all objects
+    pub fn set_air_density(&mut self, air_density: f32) {
+        self.air_density = air_density;
+    }
+
+    pub fn get_air_density(&self) -> f32 {
+        self.air_density
+    }
+
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn get_integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    pub fn set_solver(&mut self, solver: Solver) {
+        self.solver = solver;
+    }
+
+    pub fn get_solver(&self) -> &Solver {
+        &self.solver
+    }
+
+    pub fn get_mut_solver(&mut self) -> &mut Solver {
+        &mut self.solver
+    }
+
+    /// Returns the positions and the angles of moving and non-moving objects, and the outlines of soft bodies
+    pub fn create_data(&self) -> (Vec<Vec2D>, Vec<Angle>, Vec<Vec<Vec2D>>) {
         let mut pos = Vec::with_capacity(self.dyn_objects.len() + self.objects.len());
         for dyn_object in &self.dyn_objects {
             pos.push(dyn_object.get_current_position());
@@ -156,7 +951,61 @@ impl Map {
             ang.push(object.get_angle());
         }
 
-        (pos, ang)
+        let outlines = self
+            .soft_bodies
+            .iter()
+            .map(|soft_body| soft_body.get_outline())
+            .collect();
+
+        (pos, ang, outlines)
+    }
+
+    /// Returns the positions of the particles of every particle system, they are exported separately from objects
+    pub fn create_particle_data(&self) -> Vec<Vec<Vec2D>> {
+        self.particle_systems
+            .iter()
+            .map(|particle_system| {
+                particle_system
+                    .get_particles()
+                    .iter()
+                    .map(|particle| particle.position)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Same as `create_data`, but positions and angles of moving objects and outlines of soft bodies are interpolated between the last two fixed steps
+    pub fn create_interpolated_data(&self) -> (Vec<Vec2D>, Vec<Angle>, Vec<Vec<Vec2D>>) {
+        let (mut pos, mut ang, mut outlines) = self.create_data();
+        let alpha = self.get_alpha();
+
+        // objects added after the last step have nothing to interpolate with
+        for (i, (previous_position, previous_angle)) in
+            self.previous_transforms.iter().enumerate().take(self.dyn_objects.len())
+        {
+            pos[i] = *previous_position + (pos[i] - *previous_position).mul_n(alpha);
+
+            // turns along the shortest arc
+            let mut turn = ang[i].get_radian() - previous_angle.get_radian();
+            if turn > PI {
+                turn -= 2.0 * PI;
+            } else if turn < -PI {
+                turn += 2.0 * PI;
+            }
+            ang[i] = Angle::new(previous_angle.get_radian() + turn * alpha);
+        }
+
+        for (outline, previous_outline) in outlines.iter_mut().zip(&self.previous_outlines) {
+            if outline.len() != previous_outline.len() {
+                continue;
+            }
+
+            for (point, previous_point) in outline.iter_mut().zip(previous_outline) {
+                *point = *previous_point + (*point - *previous_point).mul_n(alpha);
+            }
+        }
+
+        (pos, ang, outlines)
     }
 }
 
@@ -165,3 +1014,182 @@ impl Default for Map {
         Map::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::{map_with_gravity, square};
+
+    #[test]
+    fn step_takes_the_whole_fixed_steps() {
+        let mut map = map_with_gravity(Vec2D::default());
+        map.set_fixed_time(0.01);
+
+        assert_eq!(map.step(0.035), 3);
+        assert!((map.get_alpha() - 0.5).abs() < 0.01);
+        assert_eq!(map.step(0.004), 0);
+        assert!((map.get_alpha() - 0.9).abs() < 0.01);
+        assert_eq!(map.step(0.001), 1);
+        assert!(map.get_alpha() < 0.01);
+    }
+
+    #[test]
+    fn step_drops_the_time_over_the_max_fixed_steps() {
+        let mut map = map_with_gravity(Vec2D::default());
+        map.set_fixed_time(0.01);
+        map.set_max_fixed_steps(0);
+        assert_eq!(map.get_max_fixed_steps(), 1);
+
+        map.set_max_fixed_steps(4);
+        assert_eq!(map.step(1.005), 4);
+        assert!((map.get_alpha() - 0.5).abs() < 0.01);
+
+        // the dropped time isn't caught up later
+        assert_eq!(map.step(0.0), 0);
+        assert_eq!(map.step(0.005), 1);
+    }
+
+    #[test]
+    fn alpha_interpolates_between_the_fixed_steps() {
+        let mut map = map_with_gravity(Vec2D::default());
+        map.set_fixed_time(0.01);
+        let mut dyn_object = square(Vec2D::new(100.0, 500.0), 20.0, 1.0);
+        dyn_object.set_velocity(Vec2D::new(100.0, 0.0));
+        map.dyn_objects.push(Box::new(dyn_object));
+
+        map.step(0.0125);
+        let alpha = map.get_alpha();
+        let position = map.create_interpolated_data().0[0];
+        assert!((alpha - 0.25).abs() < 0.01);
+        assert!((position.x - 100.25).abs() < 0.01);
+    }
+
+    /// Auxiliary function that creates a map with a stack of three boxes lying on the floor
+    fn stack() -> Map {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        for n in 0..3 {
+            map.dyn_objects.push(Box::new(square(
+                Vec2D::new(500.0, 990.0 - 20.0 * n as f32),
+                20.0,
+                1.0,
+            )));
+        }
+        for _ in 0..120 {
+            map.run(1.0 / 60.0);
+        }
+
+        map
+    }
+
+    #[test]
+    fn resting_stack_falls_asleep() {
+        let map = stack();
+
+        assert!(map.dyn_objects.iter().all(|dyn_object| dyn_object.is_sleeping()));
+        assert_eq!(map.get_islands().len(), 1);
+        assert_eq!(map.get_islands()[0].objects, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn impulse_wakes_the_whole_island() {
+        let mut map = stack();
+        let top = map.dyn_objects[2].get_current_position();
+        map.dyn_objects[2].apply_impulse_at_point(Vec2D::new(0.0, -50.0), top);
+        map.run(1.0 / 60.0);
+
+        assert!(map.dyn_objects.iter().all(|dyn_object| !dyn_object.is_sleeping()));
+    }
+
+    #[test]
+    fn joint_wakes_the_whole_island() {
+        let mut map = stack();
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(100.0, 500.0), 20.0, 1.0)));
+        map.weld(ObjectId::Dynamic(3), ObjectId::Dynamic(0), Vec2D::new(100.0, 500.0));
+        map.run(1.0 / 60.0);
+
+        assert!(map.dyn_objects.iter().all(|dyn_object| !dyn_object.is_sleeping()));
+    }
+
+    #[test]
+    fn threads_give_the_same_result() {
+        let maps: Vec<Map> = [1, 4]
+            .iter()
+            .map(|threads| {
+                let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+                map.set_threads(*threads);
+                for n in 0..30 {
+                    let mut dyn_object = square(
+                        Vec2D::new(100.0 + 23.0 * (n % 10) as f32, 700.0 + 25.0 * (n / 10) as f32),
+                        20.0,
+                        1.0,
+                    );
+                    dyn_object.set_velocity(Vec2D::new(50.0 - 10.0 * (n % 7) as f32, 0.0));
+                    dyn_object.set_angle_velocity(0.1 * (n % 3) as f32);
+                    map.dyn_objects.push(Box::new(dyn_object));
+                }
+                map.weld(ObjectId::Dynamic(0), ObjectId::Dynamic(1), Vec2D::new(111.5, 700.0));
+                for _ in 0..300 {
+                    map.run(1.0 / 60.0);
+                }
+
+                map
+            })
+            .collect();
+
+        // the objects are compared bit by bit, the threads must not change the order of any calculation
+        for (dyn_object1, dyn_object2) in maps[0].dyn_objects.iter().zip(&maps[1].dyn_objects) {
+            let (position1, position2) = (dyn_object1.get_current_position(), dyn_object2.get_current_position());
+            assert_eq!((position1.x, position1.y), (position2.x, position2.y));
+            assert_eq!(dyn_object1.get_angle().get_radian(), dyn_object2.get_angle().get_radian());
+        }
+    }
+
+    #[test]
+    fn forces_act_during_one_run_and_are_cleared_after_it() {
+        for substeps in [1, 4] {
+            let mut map = map_with_gravity(Vec2D::default());
+            map.set_substeps(substeps);
+            map.dyn_objects
+                .push(Box::new(square(Vec2D::new(500.0, 500.0), 20.0, 2.0)));
+
+            // the forces of several users add up instead of replacing each other
+            let dyn_object = &mut map.dyn_objects[0];
+            dyn_object.apply_force(Vec2D::new(60.0, 0.0));
+            dyn_object.apply_force(Vec2D::new(60.0, 0.0));
+            dyn_object.apply_torque(10.0);
+            dyn_object.apply_torque(10.0);
+            let inertia = 1.0 / dyn_object.get_inversion_inertia();
+            map.run(1.0 / 60.0);
+
+            let dyn_object = &map.dyn_objects[0];
+            assert!(dyn_object.get_force().len_vector(&Vec2D::default()) == 0.0 && dyn_object.get_torque() == 0.0);
+            assert!((dyn_object.get_velocity().x - 1.0).abs() < 0.001, "{}", substeps);
+            assert!((dyn_object.get_angle_velocity() - 20.0 / inertia / 60.0).abs() < 0.0001, "{}", substeps);
+
+            // the next run doesn't push the object any more
+            map.run(1.0 / 60.0);
+            assert!((map.dyn_objects[0].get_velocity().x - 1.0).abs() < 0.001, "{}", substeps);
+        }
+    }
+
+    #[test]
+    fn substeps_raise_one_contact_event_per_run() {
+        for substeps in [1, 4] {
+            let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+            map.set_time_to_sleep(f32::INFINITY);
+            map.set_substeps(substeps);
+            map.dyn_objects
+                .push(Box::new(square(Vec2D::new(500.0, 990.0), 20.0, 2.0)));
+            for _ in 0..60 {
+                map.run(1.0 / 60.0);
+            }
+
+            // the box resting on the floor gets the impulse of its weight during the whole run
+            let events = map.get_contact_events();
+            assert_eq!(events.len(), 1, "{}", substeps);
+            assert_eq!((events[0].object1, events[0].object2), (ObjectId::Dynamic(0), ObjectId::Static(2)));
+            assert!((events[0].impulse - 2.0 * 100.0 / 60.0).abs() < 0.01, "{} {}", substeps, events[0].impulse);
+        }
+    }
+}
@@ -7,3 +7,34 @@ pub mod objects;
 pub mod map;
 
 pub mod collision;
+
+pub mod query;
+
+pub mod solver;
+
+pub mod force_fields;
+
+pub mod integrator;
+
+pub mod island;
+
+pub mod joints;
+
+pub mod spring;
+
+pub mod rope;
+
+pub mod fluid;
+
+pub mod wind;
+
+pub mod soft_body;
+
+pub mod particles;
+
+pub mod pbd;
+
+pub mod character;
+
+#[cfg(test)]
+mod test_utils;
@@ -4,7 +4,7 @@ use crate::physics_engine::traits::as_object::AsObject;
 
 use super::super::{
     traits::{move_interface::MoveInterface, object_interface::ObjectInterface},
-    types::{angle::Angle, matrix2d::Matrix2D, vec2d::Vec2D},
+    types::{angle::Angle, material::Material, matrix2d::Matrix2D, vec2d::Vec2D},
 };
 
 /// Rectangle structure
@@ -17,14 +17,22 @@ pub struct Rectangle {
     inertia: f32,
     elasticity: f32,
     velocity: Vec2D,
-    friction: f32,
-    angle: Angle,
+    linear_damping: f32,
+    angle: HashMap<String, Angle>,
     angle_velocity: f32,
     angle_friction: f32,
+    material: Material,
+    drag: f32,
+    surface_velocity: f32,
+    force: Vec2D,
+    torque: f32,
+    sleeping: bool,
+    sleep_time: f32,
 }
 
 impl Rectangle {
     /// Creating a rectangle
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         first_point: Vec2D,
         second_point: Vec2D,
@@ -62,7 +70,10 @@ impl Rectangle {
             ("potential".to_string(), position),
         ]);
         let inertia = mass * (size.x.powf(2.0) + size.y.powf(2.0)) / 12.0;
-        let angle = Angle::default();
+        let angle = HashMap::from([
+            ("current".to_string(), Angle::default()),
+            ("potential".to_string(), Angle::default()),
+        ]);
 
         Rectangle {
             position,
@@ -73,10 +84,17 @@ impl Rectangle {
             inertia,
             elasticity,
             velocity,
-            friction,
+            linear_damping: 0.0,
             angle,
             angle_velocity,
             angle_friction,
+            material: Material::new(friction, friction),
+            drag: 0.0,
+            surface_velocity: 0.0,
+            force: Vec2D::default(),
+            torque: 0.0,
+            sleeping: false,
+            sleep_time: 0.0,
         }
     }
 }
@@ -92,7 +110,7 @@ impl Default for Rectangle {
             Vec2D::new(100.0, 0.0),
             0.01,
             0.0,
-            0.01,
+            0.6,
         )
     }
 }
@@ -186,19 +204,27 @@ impl ObjectInterface for Rectangle {
     }
 
     fn set_friction(&mut self, friction: f32) {
-        self.friction = friction;
+        self.material = Material::new(friction, friction);
     }
 
     fn get_friction(&self) -> f32 {
-        self.friction
+        self.material.get_dynamic_friction()
+    }
+
+    fn set_linear_damping(&mut self, linear_damping: f32) {
+        self.linear_damping = linear_damping;
+    }
+
+    fn get_linear_damping(&self) -> f32 {
+        self.linear_damping
     }
 
     fn set_angle(&mut self, angle: Angle) {
-        self.angle = angle
+        self.angle.insert("current".to_string(), angle);
     }
 
     fn get_angle(&self) -> Angle {
-        self.angle
+        self.angle["current"]
     }
 
     fn set_angle_velocity(&mut self, angle_velocity: f32) {
@@ -217,6 +243,30 @@ impl ObjectInterface for Rectangle {
         self.angle_friction
     }
 
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn get_material(&self) -> Material {
+        self.material
+    }
+
+    fn set_drag(&mut self, drag: f32) {
+        self.drag = drag;
+    }
+
+    fn get_drag(&self) -> f32 {
+        self.drag
+    }
+
+    fn set_surface_velocity(&mut self, surface_velocity: f32) {
+        self.surface_velocity = surface_velocity;
+    }
+
+    fn get_surface_velocity(&self) -> f32 {
+        self.surface_velocity
+    }
+
     fn get_circumradius(&self) -> f32 {
         (self.size.x.powf(2.0) + self.size.y.powf(2.0)).powf(0.5) / 2.0
     }
@@ -265,29 +315,32 @@ impl MoveInterface for Rectangle {
             self.position["current"] + Vec2D::new(self.velocity.x * time, self.velocity.y * time),
         );
 
-        self.angle = Angle::new(self.angle.get_radian() + self.angle_velocity);
-        self.angle_velocity *= 1.0 - self.angle_friction;
-        let rotation_matrix = Matrix2D::rotation_matrix(&self.angle);
+        self.angle.insert(
+            "potential".to_string(),
+            Angle::new(self.angle["current"].get_radian() + self.angle_velocity * time),
+        );
+        let rotation_matrix = Matrix2D::rotation_matrix(&self.angle["potential"]);
         self.direction.insert(
             "current".to_string(),
             rotation_matrix.multiply_vec2d(&self.direction["sample"]),
         );
 
+        // vertices go around the perimeter, so neighbouring vertices form the edges of the rectangle
         self.vertex.insert(
             "potential".to_string(),
             [
-                self.position["current"]
-                    + self.direction["current"].mul_n(-self.size.y / 2.0)
-                    + self.direction["current"].normal().mul_n(-self.size.x / 2.0),
-                self.position["current"]
+                self.position["potential"]
                     + self.direction["current"].mul_n(-self.size.y / 2.0)
                     + self.direction["current"].normal().mul_n(self.size.x / 2.0),
-                self.position["current"]
-                    + self.direction["current"].mul_n(self.size.y / 2.0)
-                    + self.direction["current"].normal().mul_n(-self.size.x / 2.0),
-                self.position["current"]
+                self.position["potential"]
                     + self.direction["current"].mul_n(self.size.y / 2.0)
                     + self.direction["current"].normal().mul_n(self.size.x / 2.0),
+                self.position["potential"]
+                    + self.direction["current"].mul_n(self.size.y / 2.0)
+                    + self.direction["current"].normal().mul_n(-self.size.x / 2.0),
+                self.position["potential"]
+                    + self.direction["current"].mul_n(-self.size.y / 2.0)
+                    + self.direction["current"].normal().mul_n(-self.size.x / 2.0),
             ],
         );
     }
@@ -297,6 +350,8 @@ impl MoveInterface for Rectangle {
 
         self.position
             .insert("current".to_string(), self.position["potential"]);
+        self.angle
+            .insert("current".to_string(), self.angle["potential"]);
         self.vertex.insert(
             "current".to_string(),
             [
@@ -330,13 +385,14 @@ impl MoveInterface for Rectangle {
         let mut main_object = false;
 
         // looks at the projections if there is an overflow
-        for i in 0..axes.len() {
-            self.as_object().projection_on_axis(&axes[i]);
-            let (max1, min1, _) = self.as_object().projection_on_axis(&axes[i]);
-            let (max2, min2, _) = object.projection_on_axis(&axes[i]);
+        for (i, axis) in axes.iter_mut().enumerate() {
+            self.as_object().projection_on_axis(axis);
+            let (max1, min1, _) = self.as_object().projection_on_axis(axis);
+            let (max2, min2, _) = object.projection_on_axis(axis);
 
             let mut overlap = max1.min(max2) - min1.max(min2);
-            if overlap <= 0.0 {
+            // a line projects onto its own normal as a point, so zero overlap still counts
+            if overlap < 0.0 {
                 return None;
             }
 
@@ -348,7 +404,7 @@ impl MoveInterface for Rectangle {
                     overlap += min
                 } else {
                     overlap += max;
-                    axes[i] = axes[i].mul_n(-1.0);
+                    *axis = axis.mul_n(-1.0);
                 }
             }
 
@@ -357,7 +413,7 @@ impl MoveInterface for Rectangle {
                 Some(j) if overlap >= j => (),
                 _ => {
                     min_overlap = Some(overlap);
-                    smallest_axis = axes[i];
+                    smallest_axis = *axis;
 
                     if i < 2 {
                         main_object = false;
@@ -385,4 +441,66 @@ impl MoveInterface for Rectangle {
 
         Some((min_overlap.unwrap(), smallest_axis, contact_vertex))
     }
+
+    fn apply_force(&mut self, force: Vec2D) {
+        self.wake();
+        self.force += force;
+    }
+
+    fn apply_force_at_point(&mut self, force: Vec2D, point: Vec2D) {
+        self.wake();
+        self.force += force;
+        self.torque += Vec2D::cross(&(point - self.position["current"]), &force);
+    }
+
+    fn apply_torque(&mut self, torque: f32) {
+        self.wake();
+        self.torque += torque;
+    }
+
+    fn apply_linear_impulse(&mut self, impulse: Vec2D) {
+        self.wake();
+        self.velocity += impulse.mul_n(self.get_inversion_mass());
+    }
+
+    fn apply_impulse_at_point(&mut self, impulse: Vec2D, point: Vec2D) {
+        self.wake();
+        self.velocity += impulse.mul_n(self.get_inversion_mass());
+        self.angle_velocity += self.get_inversion_inertia()
+            * Vec2D::cross(&(point - self.position["current"]), &impulse);
+    }
+
+    fn get_force(&self) -> Vec2D {
+        self.force
+    }
+
+    fn get_torque(&self) -> f32 {
+        self.torque
+    }
+
+    fn clear_forces(&mut self) {
+        self.force = Vec2D::default();
+        self.torque = 0.0;
+    }
+
+    fn set_sleeping(&mut self, sleeping: bool) {
+        self.sleeping = sleeping;
+    }
+
+    fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    fn set_sleep_time(&mut self, sleep_time: f32) {
+        self.sleep_time = sleep_time;
+    }
+
+    fn get_sleep_time(&self) -> f32 {
+        self.sleep_time
+    }
+
+    fn wake(&mut self) {
+        self.sleeping = false;
+        self.sleep_time = 0.0;
+    }
 }
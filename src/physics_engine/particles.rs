@@ -0,0 +1,341 @@
+use super::{
+    query::raycast,
+    traits::{force_field_interface::ForceFieldInterface, object_interface::ObjectInterface},
+    types::{angle::Angle, matrix2d::Matrix2D, vec2d::Vec2D},
+};
+
+/// Point without size and mass that lives for a limited time
+#[derive(Debug, Copy, Clone)]
+pub struct Particle {
+    pub position: Vec2D,
+    pub velocity: Vec2D,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+/// Source that creates particles at a constant rate
+#[derive(Debug, Copy, Clone)]
+pub struct Emitter {
+    position: Vec2D,
+    velocity: Vec2D,
+    spread: f32,
+    speed_variation: f32,
+    rate: f32,
+    lifetime: f32,
+    enabled: bool,
+    accumulated: f32,
+}
+
+impl Emitter {
+    /// Creating an emitter, the rate is the number of particles per second
+    pub fn new(position: Vec2D, velocity: Vec2D, rate: f32, lifetime: f32) -> Self {
+        Emitter {
+            position,
+            velocity,
+            spread: 0.0,
+            speed_variation: 0.0,
+            rate,
+            lifetime,
+            enabled: true,
+            accumulated: 0.0,
+        }
+    }
+
+    pub fn set_position(&mut self, position: Vec2D) {
+        self.position = position;
+    }
+
+    pub fn get_position(&self) -> Vec2D {
+        self.position
+    }
+
+    /// Velocity of new particles before the spread
+    pub fn set_velocity(&mut self, velocity: Vec2D) {
+        self.velocity = velocity;
+    }
+
+    pub fn get_velocity(&self) -> Vec2D {
+        self.velocity
+    }
+
+    /// The largest angle in radians by which the velocity of a new particle is turned in either direction
+    pub fn set_spread(&mut self, spread: f32) {
+        self.spread = spread;
+    }
+
+    pub fn get_spread(&self) -> f32 {
+        self.spread
+    }
+
+    /// The largest part of the speed by which the speed of a new particle is changed in either direction
+    pub fn set_speed_variation(&mut self, speed_variation: f32) {
+        self.speed_variation = speed_variation;
+    }
+
+    pub fn get_speed_variation(&self) -> f32 {
+        self.speed_variation
+    }
+
+    pub fn set_rate(&mut self, rate: f32) {
+        self.rate = rate;
+    }
+
+    pub fn get_rate(&self) -> f32 {
+        self.rate
+    }
+
+    pub fn set_lifetime(&mut self, lifetime: f32) {
+        self.lifetime = lifetime;
+    }
+
+    pub fn get_lifetime(&self) -> f32 {
+        self.lifetime
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn get_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Set of cheap particles moved by gravity and force fields, e.g. dust or grains
+///
+/// Particles don't touch each other and moving objects, they bounce only off non-moving objects.
+/// The way of a particle during the step is cast as a ray against the objects whose bounding boxes it crosses
+pub struct ParticleSystem {
+    pub emitters: Vec<Emitter>,
+    particles: Vec<Particle>,
+    max_particles: usize,
+    elasticity: f32,
+    friction: f32,
+    seed: u32,
+}
+
+impl ParticleSystem {
+    /// Creating a particle system, emitters stop creating particles when their number reaches the max
+    pub fn new(max_particles: usize) -> Self {
+        ParticleSystem {
+            emitters: Vec::<Emitter>::new(),
+            particles: Vec::<Particle>::with_capacity(max_particles),
+            max_particles,
+            elasticity: 0.3,
+            friction: 0.2,
+            seed: 0x9e37_79b9,
+        }
+    }
+
+    pub fn get_particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Adds a particle, if the number of particles is less than the max
+    pub fn emit(&mut self, position: Vec2D, velocity: Vec2D, lifetime: f32) {
+        if self.particles.len() < self.max_particles {
+            self.particles.push(Particle {
+                position,
+                velocity,
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Removes all particles
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn set_max_particles(&mut self, max_particles: usize) {
+        self.max_particles = max_particles;
+    }
+
+    pub fn get_max_particles(&self) -> usize {
+        self.max_particles
+    }
+
+    /// Part of the normal velocity that a particle keeps after bouncing
+    pub fn set_elasticity(&mut self, elasticity: f32) {
+        self.elasticity = elasticity;
+    }
+
+    pub fn get_elasticity(&self) -> f32 {
+        self.elasticity
+    }
+
+    /// Part of the tangent velocity that a particle loses after bouncing
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction;
+    }
+
+    pub fn get_friction(&self) -> f32 {
+        self.friction
+    }
+
+    /// Seed of the random spread of the emitters, the same seed gives the same particles
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed.max(1);
+    }
+
+    /// Function creates, moves and removes particles
+    pub fn run(
+        &mut self,
+        time: f32,
+        gravity: Vec2D,
+        force_fields: &[Box<dyn ForceFieldInterface>],
+        objects: &[Box<dyn ObjectInterface>],
+    ) {
+        self.emit_from_emitters(time);
+
+        for particle in self.particles.iter_mut() {
+            particle.age += time;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+
+        // bounding boxes of the objects let particles skip the objects far from them
+        let bounds: Vec<(Vec2D, Vec2D)> = objects
+            .iter()
+            .map(|object| ParticleSystem::get_bounds(&object.get_potential_vertex()))
+            .collect();
+
+        for particle in self.particles.iter_mut() {
+            let acceleration = force_fields.iter().fold(gravity, |acceleration, force_field| {
+                acceleration + force_field.get_acceleration_at(&particle.position)
+            });
+            particle.velocity += acceleration.mul_n(time);
+            let end = particle.position + particle.velocity.mul_n(time);
+
+            // finds the first object on the way of the particle
+            let (min, max) = ParticleSystem::get_bounds(&[particle.position, end]);
+            let hit = objects
+                .iter()
+                .zip(&bounds)
+                .filter(|(_, (object_min, object_max))| {
+                    min.x <= object_max.x && max.x >= object_min.x && min.y <= object_max.y && max.y >= object_min.y
+                })
+                .filter_map(|(object, _)| raycast(object.as_ref(), &particle.position, &end))
+                .min_by(|hit1, hit2| hit1.0.total_cmp(&hit2.0));
+
+            match hit {
+                Some((_, point, normal)) => {
+                    // the particle bounces off the object and stays slightly in front of it for the rest of the step
+                    let normal_velocity = normal.mul_n(Vec2D::dot(&particle.velocity, &normal));
+                    let tangent_velocity = particle.velocity - normal_velocity;
+                    particle.velocity = tangent_velocity.mul_n(1.0 - self.friction)
+                        - normal_velocity.mul_n(self.elasticity);
+                    particle.position = point + normal.mul_n(0.01);
+                }
+                None => particle.position = end,
+            }
+        }
+    }
+
+    /// Auxiliary function that creates the particles of the emitters accumulated during the time
+    fn emit_from_emitters(&mut self, time: f32) {
+        for n in 0..self.emitters.len() {
+            if !self.emitters[n].enabled {
+                continue;
+            }
+
+            self.emitters[n].accumulated += self.emitters[n].rate * time;
+            while self.emitters[n].accumulated >= 1.0 {
+                self.emitters[n].accumulated -= 1.0;
+
+                let emitter = self.emitters[n];
+                let turn = Angle::new(emitter.spread * self.get_random());
+                let speed = 1.0 + emitter.speed_variation * self.get_random();
+                let velocity = Matrix2D::rotation_matrix(&turn)
+                    .multiply_vec2d(&emitter.velocity)
+                    .mul_n(speed);
+                self.emit(emitter.position, velocity, emitter.lifetime);
+            }
+        }
+    }
+
+    /// Auxiliary function that returns a pseudo-random number from -1 to 1 with the xorshift generator
+    fn get_random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+
+        self.seed as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    /// Auxiliary function for finding the left top and the right bottom points of the box around the points
+    fn get_bounds(points: &[Vec2D]) -> (Vec2D, Vec2D) {
+        points.iter().fold(
+            (
+                Vec2D::new(f32::INFINITY, f32::INFINITY),
+                Vec2D::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            ),
+            |(min, max), point| {
+                (
+                    Vec2D::new(min.x.min(point.x), min.y.min(point.y)),
+                    Vec2D::new(max.x.max(point.x), max.y.max(point.y)),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::test_utils::map_with_gravity;
+
+    #[test]
+    fn particle_bounces_off_the_floor() {
+        let mut map = map_with_gravity(Vec2D::default());
+        let mut particle_system = ParticleSystem::new(10);
+        particle_system.emit(Vec2D::new(500.0, 999.0), Vec2D::new(60.0, 120.0), 10.0);
+        map.particle_systems.push(particle_system);
+        map.run(1.0 / 60.0);
+
+        // the particle keeps the part of the normal velocity and loses the part of the tangent one
+        let particle = map.particle_systems[0].get_particles()[0];
+        assert!((particle.velocity.y + 120.0 * 0.3).abs() < 0.001, "{:?}", particle.velocity);
+        assert!((particle.velocity.x - 60.0 * 0.8).abs() < 0.001, "{:?}", particle.velocity);
+        assert!(particle.position.y < 1000.0 && particle.position.y > 999.9, "{:?}", particle.position);
+
+        // it doesn't pass through the floor afterwards
+        for _ in 0..120 {
+            map.run(1.0 / 60.0);
+        }
+        assert!(map.particle_systems[0].get_particles()[0].position.y < 1000.0);
+    }
+
+    #[test]
+    fn particles_live_for_their_lifetime() {
+        let mut map = map_with_gravity(Vec2D::default());
+        let mut particle_system = ParticleSystem::new(100);
+        particle_system
+            .emitters
+            .push(Emitter::new(Vec2D::new(500.0, 500.0), Vec2D::new(10.0, 0.0), 60.0, 0.5));
+        map.particle_systems.push(particle_system);
+
+        // an emitter of 60 particles per second keeps 30 particles living for half a second
+        for _ in 0..120 {
+            map.run(1.0 / 60.0);
+            let particles = map.particle_systems[0].get_particles();
+            assert!(particles.len() <= 30, "{}", particles.len());
+            assert!(particles.iter().all(|particle| particle.age < 0.5));
+        }
+        assert!(map.particle_systems[0].get_particles().len() >= 29);
+
+        // the max number of particles stops the emitter, the disabled emitter lets the particles die out
+        map.particle_systems[0].set_max_particles(10);
+        map.particle_systems[0].clear();
+        for _ in 0..20 {
+            map.run(1.0 / 60.0);
+        }
+        assert_eq!(map.particle_systems[0].get_particles().len(), 10);
+
+        map.particle_systems[0].emitters[0].set_enabled(false);
+        for _ in 0..30 {
+            map.run(1.0 / 60.0);
+        }
+        assert!(map.particle_systems[0].get_particles().is_empty());
+    }
+}
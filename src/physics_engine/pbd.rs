@@ -0,0 +1,322 @@
+use super::{
+    query::point_contact,
+    traits::{
+        force_field_interface::ForceFieldInterface, move_interface::MoveInterface,
+        object_interface::ObjectInterface,
+    },
+    types::vec2d::Vec2D,
+};
+
+/// Particle of a position-based body, its velocity is the difference of the position and the previous position
+#[derive(Debug, Copy, Clone)]
+pub struct PbdParticle {
+    pub position: Vec2D,
+    pub previous_position: Vec2D,
+    pub inversion_mass: f32,
+    pinned: bool,
+}
+
+impl PbdParticle {
+    /// Pinned particles are not moved by forces and constraints, they stay where they are placed
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+/// Cloth sheet or rope made of particles moved with the Verlet integration and held by position constraints
+///
+/// Distance constraints keep the neighbouring particles at their rest distance, bending constraints keep the particles
+/// through one at their rest distance, so that the body resists folding. The body collides one way: it is pushed out of
+/// non-moving and moving objects, but doesn't push them
+pub struct PbdBody {
+    particles: Vec<PbdParticle>,
+    distance_constraints: Vec<(usize, usize, f32)>,
+    bending_constraints: Vec<(usize, usize, f32)>,
+    stiffness: f32,
+    bending_stiffness: f32,
+    damping: f32,
+    friction: f32,
+    iterations: usize,
+    previous_time: f32,
+}
+
+impl PbdBody {
+    /// Creating a body from the particles without constraints, the mass is spread evenly over the particles
+    pub fn new(points: Vec<Vec2D>, mass: f32) -> Self {
+        let inversion_mass = if mass == 0.0 || points.is_empty() {
+            0.0
+        } else {
+            points.len() as f32 / mass
+        };
+
+        PbdBody {
+            particles: points
+                .into_iter()
+                .map(|position| PbdParticle {
+                    position,
+                    previous_position: position,
+                    inversion_mass,
+                    pinned: false,
+                })
+                .collect(),
+            distance_constraints: Vec::<(usize, usize, f32)>::new(),
+            bending_constraints: Vec::<(usize, usize, f32)>::new(),
+            stiffness: 1.0,
+            bending_stiffness: 0.1,
+            damping: 0.5,
+            friction: 0.3,
+            iterations: 10,
+            previous_time: 0.0,
+        }
+    }
+
+    /// Creating a rope from the start to the end point with the number of segments
+    pub fn rope(start: Vec2D, end: Vec2D, segments: usize, mass: f32) -> Self {
+        let segments = segments.max(1);
+        let edge = (end - start).mul_n(1.0 / segments as f32);
+        let mut rope = PbdBody::new(
+            (0..=segments).map(|n| start + edge.mul_n(n as f32)).collect(),
+            mass,
+        );
+
+        for n in 0..segments {
+            rope.add_distance_constraint(n, n + 1);
+            if n + 2 <= segments {
+                rope.add_bending_constraint(n, n + 2);
+            }
+        }
+
+        rope
+    }
+
+    /// Creating a cloth sheet in the form of a grid of particles between the left top and the right bottom points
+    ///
+    /// Particles are indexed by rows from the left top one
+    pub fn cloth(plt: Vec2D, prb: Vec2D, columns: usize, rows: usize, mass: f32) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let cell = Vec2D::new((prb.x - plt.x) / columns as f32, (prb.y - plt.y) / rows as f32);
+        let index = |column: usize, row: usize| row * (columns + 1) + column;
+
+        let mut points = Vec::<Vec2D>::with_capacity((columns + 1) * (rows + 1));
+        for row in 0..=rows {
+            for column in 0..=columns {
+                points.push(plt + Vec2D::new(cell.x * column as f32, cell.y * row as f32));
+            }
+        }
+
+        let mut cloth = PbdBody::new(points, mass);
+        for row in 0..=rows {
+            for column in 0..=columns {
+                if column < columns {
+                    cloth.add_distance_constraint(index(column, row), index(column + 1, row));
+                }
+                if row < rows {
+                    cloth.add_distance_constraint(index(column, row), index(column, row + 1));
+                }
+                if column + 2 <= columns {
+                    cloth.add_bending_constraint(index(column, row), index(column + 2, row));
+                }
+                if row + 2 <= rows {
+                    cloth.add_bending_constraint(index(column, row), index(column, row + 2));
+                }
+            }
+        }
+
+        cloth
+    }
+
+    /// Keeps the current distance between two particles
+    pub fn add_distance_constraint(&mut self, particle1: usize, particle2: usize) {
+        let rest_length = self.get_distance(particle1, particle2);
+        self.distance_constraints.push((particle1, particle2, rest_length));
+    }
+
+    /// Softly keeps the current distance between two particles with the bending stiffness
+    pub fn add_bending_constraint(&mut self, particle1: usize, particle2: usize) {
+        let rest_length = self.get_distance(particle1, particle2);
+        self.bending_constraints.push((particle1, particle2, rest_length));
+    }
+
+    pub fn get_distance_constraints(&self) -> &[(usize, usize, f32)] {
+        &self.distance_constraints
+    }
+
+    pub fn get_bending_constraints(&self) -> &[(usize, usize, f32)] {
+        &self.bending_constraints
+    }
+
+    pub fn get_particles(&self) -> &[PbdParticle] {
+        &self.particles
+    }
+
+    /// Returns the positions of all particles
+    pub fn get_positions(&self) -> Vec<Vec2D> {
+        self.particles.iter().map(|particle| particle.position).collect()
+    }
+
+    /// Pins the particle at its place or releases it
+    pub fn set_pinned(&mut self, particle: usize, pinned: bool) {
+        self.particles[particle].pinned = pinned;
+        self.particles[particle].previous_position = self.particles[particle].position;
+    }
+
+    /// Moves the particle to the position without giving it a velocity, e.g. to move a pinned particle
+    pub fn set_position(&mut self, particle: usize, position: Vec2D) {
+        self.particles[particle].position = position;
+        self.particles[particle].previous_position = position;
+    }
+
+    /// Part of the error that distance constraints correct at every iteration, from zero to one (rigid)
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.stiffness = stiffness.clamp(0.0, 1.0);
+    }
+
+    pub fn get_stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    /// Part of the error that bending constraints correct at every iteration, from zero (folds freely) to one
+    pub fn set_bending_stiffness(&mut self, bending_stiffness: f32) {
+        self.bending_stiffness = bending_stiffness.clamp(0.0, 1.0);
+    }
+
+    pub fn get_bending_stiffness(&self) -> f32 {
+        self.bending_stiffness
+    }
+
+    /// Damping of the velocity of particles per second, so it doesn't depend on the length of the step
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.max(0.0);
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Part of the tangent movement that particles lose when they touch objects
+    pub fn set_friction(&mut self, friction: f32) {
+        self.friction = friction.clamp(0.0, 1.0);
+    }
+
+    pub fn get_friction(&self) -> f32 {
+        self.friction
+    }
+
+    /// Number of times the constraints and collisions are resolved at every step
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations.max(1);
+    }
+
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Function moves the particles and resolves the constraints and collisions with the objects of the map
+    pub fn run(
+        &mut self,
+        time: f32,
+        gravity: Vec2D,
+        force_fields: &[Box<dyn ForceFieldInterface>],
+        objects: &[Box<dyn ObjectInterface>],
+        dyn_objects: &[Box<dyn MoveInterface>],
+    ) {
+        // the Verlet integration keeps the velocity as the difference of the positions, which is rescaled if the time of the step changes
+        let scale = if self.previous_time > 0.0 {
+            time / self.previous_time * (-self.damping * time).exp()
+        } else {
+            (-self.damping * time).exp()
+        };
+        self.previous_time = time;
+
+        let start: Vec<Vec2D> = self.get_positions();
+        for particle in self.particles.iter_mut() {
+            if particle.pinned || particle.inversion_mass == 0.0 {
+                particle.previous_position = particle.position;
+                continue;
+            }
+
+            let acceleration = force_fields.iter().fold(gravity, |acceleration, force_field| {
+                acceleration + force_field.get_acceleration_at(&particle.position)
+            });
+            let displacement = (particle.position - particle.previous_position).mul_n(scale);
+            particle.previous_position = particle.position;
+            particle.position += displacement + acceleration.mul_n(time * time);
+        }
+
+        // objects don't move while the body is solved, so their vertices are taken once
+        let polygons: Vec<Vec<Vec2D>> = objects
+            .iter()
+            .map(|object| object.get_potential_vertex())
+            .chain(dyn_objects.iter().map(|dyn_object| dyn_object.get_potential_vertex()))
+            .collect();
+
+        for _ in 0..self.iterations {
+            for n in 0..self.distance_constraints.len() {
+                let (particle1, particle2, rest_length) = self.distance_constraints[n];
+                self.solve_distance(particle1, particle2, rest_length, self.stiffness);
+            }
+
+            for n in 0..self.bending_constraints.len() {
+                let (particle1, particle2, rest_length) = self.bending_constraints[n];
+                self.solve_distance(particle1, particle2, rest_length, self.bending_stiffness);
+            }
+
+            for (i, previous) in start.iter().enumerate() {
+                for vertices in &polygons {
+                    self.collide(i, *previous, vertices);
+                }
+            }
+        }
+    }
+
+    /// Auxiliary function that moves two particles to the rest distance by the part of the error
+    fn solve_distance(&mut self, particle1: usize, particle2: usize, rest_length: f32, stiffness: f32) {
+        let inversion_mass1 = self.get_inversion_mass(particle1);
+        let inversion_mass2 = self.get_inversion_mass(particle2);
+        let inversion_mass = inversion_mass1 + inversion_mass2;
+        if inversion_mass == 0.0 {
+            return;
+        }
+
+        let distance = self.particles[particle2].position - self.particles[particle1].position;
+        let error = distance.len_vector(&Vec2D::default()) - rest_length;
+        let correction = distance.unit().mul_n(error * stiffness / inversion_mass);
+        self.particles[particle1].position += correction.mul_n(inversion_mass1);
+        self.particles[particle2].position -= correction.mul_n(inversion_mass2);
+    }
+
+    /// Auxiliary function that pushes the particle out of the object, the particle loses a part of its tangent movement
+    fn collide(&mut self, i: usize, previous: Vec2D, vertices: &[Vec2D]) {
+        if self.get_inversion_mass(i) == 0.0 {
+            return;
+        }
+
+        let particle = &mut self.particles[i];
+        if let Some(contact) = point_contact(particle.position, previous, vertices) {
+            particle.position = contact.position;
+
+            // the previous position is moved, so that the particle doesn't move into the object at the next step
+            let movement = particle.position - particle.previous_position;
+            let normal_movement = contact.normal.mul_n(Vec2D::dot(&movement, &contact.normal).min(0.0));
+            let tangent_movement = movement - contact.normal.mul_n(Vec2D::dot(&movement, &contact.normal));
+            particle.previous_position += normal_movement + tangent_movement.mul_n(self.friction);
+        }
+    }
+
+    /// Auxiliary function that returns the inversion mass of the particle, pinned particles have infinite mass
+    fn get_inversion_mass(&self, particle: usize) -> f32 {
+        if self.particles[particle].pinned {
+            0.0
+        } else {
+            self.particles[particle].inversion_mass
+        }
+    }
+
+    /// Auxiliary function for calculating the distance between two particles
+    fn get_distance(&self, particle1: usize, particle2: usize) -> f32 {
+        self.particles[particle1]
+            .position
+            .len_vector(&self.particles[particle2].position)
+    }
+}
@@ -0,0 +1,521 @@
+use super::{traits::object_interface::ObjectInterface, types::vec2d::Vec2D};
+
+/// Calculates the distance between two objects and the closest points on each of them
+///
+/// Returns: `(distance, point1, point2)`, where `point1` lies on `object1` and `point2` lies on `object2`.
+///
+/// If the objects overlap, the distance is negative and equals the penetration depth,
+/// `point2` is the deepest point of `object2` inside `object1` and `point1` is the point where it has to be pushed to separate the objects
+pub fn distance(object1: &dyn ObjectInterface, object2: &dyn ObjectInterface) -> (f32, Vec2D, Vec2D) {
+    let polygon1 = object1.get_potential_vertex();
+    let polygon2 = object2.get_potential_vertex();
+
+    // overlapping objects get the penetration depth along the axis of the least overlap
+    if let Some((depth, normal)) = penetration(&polygon1, &polygon2) {
+        let point2 = support_point(&polygon2, &normal.mul_n(-1.0));
+        let point1 = point2 + normal.mul_n(depth);
+
+        return (-depth, point1, point2);
+    }
+
+    let edges1 = edges(&polygon1);
+    let edges2 = edges(&polygon2);
+
+    // the closest points of separated convex polygons always include a vertex of one of them
+    let mut result = (f32::MAX, Vec2D::default(), Vec2D::default());
+    for vertex in &polygon1 {
+        for edge in &edges2 {
+            let point = closest_point_on_segment(vertex, &edge.0, &edge.1);
+            let distance = vertex.len_vector(&point);
+            if distance < result.0 {
+                result = (distance, *vertex, point);
+            }
+        }
+    }
+
+    for vertex in &polygon2 {
+        for edge in &edges1 {
+            let point = closest_point_on_segment(vertex, &edge.0, &edge.1);
+            let distance = vertex.len_vector(&point);
+            if distance < result.0 {
+                result = (distance, point, *vertex);
+            }
+        }
+    }
+
+    result
+}
+
+/// Casts the ray from the origin to the end point against the edges of the object
+///
+/// Returns: `None` - if the ray doesn't hit the object or
+///
+/// `Some((fraction, point, normal))` - the part of the way from the origin to the end passed before the hit, the point of the hit
+/// and the unit normal of the hit edge facing the origin
+pub fn raycast(object: &dyn ObjectInterface, origin: &Vec2D, end: &Vec2D) -> Option<(f32, Vec2D, Vec2D)> {
+    cast_ray(&object.get_potential_vertex(), origin, end)
+}
+
+/// Casts the convex polygon moved by the displacement against the object, the polygon may also be a segment
+///
+/// Returns: `None` - if the polygon doesn't hit the object on the way or
+///
+/// `Some((fraction, point, normal))` - the part of the displacement passed before the hit, the point of the contact
+/// and the unit normal of the surface of the object facing the polygon
+pub fn shape_cast(polygon: &[Vec2D], displacement: &Vec2D, object: &dyn ObjectInterface) -> Option<(f32, Vec2D, Vec2D)> {
+    let vertices = object.get_potential_vertex();
+
+    // the vertices of the polygon may hit the edges of the object, and the vertices of the object may hit the edges of the polygon
+    let polygon_hits = polygon
+        .iter()
+        .filter_map(|vertex| cast_ray(&vertices, vertex, &(*vertex + *displacement)));
+    let object_hits = vertices.iter().filter_map(|vertex| {
+        cast_ray(polygon, vertex, &(*vertex - *displacement))
+            .map(|(fraction, _, normal)| (fraction, *vertex, normal.mul_n(-1.0)))
+    });
+
+    polygon_hits
+        .chain(object_hits)
+        .min_by(|hit1, hit2| hit1.0.total_cmp(&hit2.0))
+}
+
+/// Calculates the signed area of the polygon, which is positive for the vertices going clockwise on the screen
+pub fn signed_area(polygon: &[Vec2D]) -> f32 {
+    (0..polygon.len()).fold(0.0, |area, n| {
+        area + Vec2D::cross(&polygon[n], &polygon[(n + 1) % polygon.len()])
+    }) / 2.0
+}
+
+/// Response of a point to the collision with an object
+pub struct PointContact {
+    /// Position of the point pushed out of the object
+    pub position: Vec2D,
+    /// Unit normal of the surface of the object at the contact
+    pub normal: Vec2D,
+    /// Point of the surface of the object where the contact is
+    pub point: Vec2D,
+}
+
+/// Finds the contact of the moving point with the polygon or the segment of an object
+///
+/// A point inside a polygon is pushed out through the nearest edge, a point that crossed a segment on the way from `previous` is returned to its side.
+///
+/// Returns: `None` - if the point doesn't touch the object or
+///
+/// `Some(contact)` - the pushed out position of the point, the normal and the point of the surface
+pub fn point_contact(position: Vec2D, previous: Vec2D, vertices: &[Vec2D]) -> Option<PointContact> {
+    if vertices.len() == 2 {
+        let edge = vertices[1] - vertices[0];
+        let previous_side = Vec2D::cross(&edge, &(previous - vertices[0]));
+        let side = Vec2D::cross(&edge, &(position - vertices[0]));
+        if previous_side == 0.0 || previous_side.signum() == side.signum() && side != 0.0 {
+            return None;
+        }
+
+        let crossing =
+            previous + (position - previous).mul_n(previous_side / (previous_side - side));
+        let along = Vec2D::dot(&(crossing - vertices[0]), &edge) / Vec2D::dot(&edge, &edge);
+        if !(0.0..=1.0).contains(&along) {
+            return None;
+        }
+
+        // the point is left slightly above the segment, so that it doesn't cross it again at once
+        let normal = edge.normal().unit().mul_n(previous_side.signum());
+        return Some(PointContact {
+            position: crossing + normal.mul_n(0.01),
+            normal,
+            point: crossing,
+        });
+    }
+
+    if vertices.len() < 3 {
+        return None;
+    }
+
+    let orientation = signed_area(vertices).signum();
+    let mut contact: Option<(f32, Vec2D)> = None;
+    for n in 0..vertices.len() {
+        let edge = vertices[(n + 1) % vertices.len()] - vertices[n];
+        let depth = Vec2D::cross(&edge, &(position - vertices[n])) * orientation
+            / edge.len_vector(&Vec2D::default());
+        if depth < 0.0 {
+            return None;
+        }
+
+        if contact.is_none_or(|(min_depth, _)| depth < min_depth) {
+            contact = Some((depth, edge.normal().unit().mul_n(-orientation)));
+        }
+    }
+
+    contact.map(|(depth, normal)| PointContact {
+        position: position + normal.mul_n(depth),
+        normal,
+        point: position + normal.mul_n(depth),
+    })
+}
+
+/// Auxiliary function that casts the ray against the edges of the polygon, see `raycast`
+fn cast_ray(polygon: &[Vec2D], origin: &Vec2D, end: &Vec2D) -> Option<(f32, Vec2D, Vec2D)> {
+    let ray = *end - *origin;
+    let mut result: Option<(f32, Vec2D, Vec2D)> = None;
+
+    for (first_point, second_point) in edges(polygon) {
+        let edge = second_point - first_point;
+        let denominator = Vec2D::cross(&ray, &edge);
+        if denominator == 0.0 {
+            continue;
+        }
+
+        // the ray and the edge cross if both parameters of the crossing lie in their segments
+        let offset = first_point - *origin;
+        let fraction = Vec2D::cross(&offset, &edge) / denominator;
+        let along = Vec2D::cross(&offset, &ray) / denominator;
+        if !(0.0..=1.0).contains(&fraction) || !(0.0..=1.0).contains(&along) {
+            continue;
+        }
+
+        if result.is_none_or(|(min_fraction, _, _)| fraction < min_fraction) {
+            let normal = edge.normal().unit();
+            let normal = if Vec2D::dot(&normal, &ray) > 0.0 {
+                normal.mul_n(-1.0)
+            } else {
+                normal
+            };
+            result = Some((fraction, *origin + ray.mul_n(fraction), normal));
+        }
+    }
+
+    result
+}
+
+/// Auxiliary function for finding the closest point of the segment `[first_point, second_point]` to the point
+fn closest_point_on_segment(point: &Vec2D, first_point: &Vec2D, second_point: &Vec2D) -> Vec2D {
+    let edge = *second_point - *first_point;
+    let edge_len = Vec2D::dot(&edge, &edge);
+
+    if edge_len == 0.0 {
+        return *first_point;
+    }
+
+    let t = (Vec2D::dot(&(*point - *first_point), &edge) / edge_len).clamp(0.0, 1.0);
+
+    *first_point + edge.mul_n(t)
+}
+
+/// Auxiliary function that splits the vertices of a convex polygon into edges
+fn edges(polygon: &[Vec2D]) -> Vec<(Vec2D, Vec2D)> {
+    match polygon.len() {
+        0 | 1 => Vec::new(),
+        2 => vec![(polygon[0], polygon[1])],
+        len => (0..len)
+            .map(|i| (polygon[i], polygon[(i + 1) % len]))
+            .collect(),
+    }
+}
+
+/// Auxiliary function for finding the vertex of a polygon lying furthest along the direction
+fn support_point(polygon: &[Vec2D], direction: &Vec2D) -> Vec2D {
+    let mut support = polygon[0];
+    let mut max = Vec2D::dot(direction, &support);
+
+    for vertex in polygon {
+        let p = Vec2D::dot(direction, vertex);
+        if p > max {
+            max = p;
+            support = *vertex;
+        }
+    }
+
+    support
+}
+
+/// Auxiliary function that checks two convex polygons for overlap with the sat method
+///
+/// Returns: `None` - if polygons do not overlap or
+///
+/// `Some((depth, normal))` - the smallest depth of penetration and the unit axis along which the second polygon has to be moved to separate the polygons
+fn penetration(polygon1: &[Vec2D], polygon2: &[Vec2D]) -> Option<(f32, Vec2D)> {
+    let mut axes: Vec<Vec2D> = edges(polygon1)
+        .iter()
+        .map(|edge| (edge.1 - edge.0).unit().normal())
+        .collect();
+    axes.extend(
+        edges(polygon2)
+            .iter()
+            .map(|edge| (edge.1 - edge.0).unit().normal()),
+    );
+
+    let mut result: Option<(f32, Vec2D)> = None;
+    for axis in axes {
+        let (max1, min1) = projection(polygon1, &axis);
+        let (max2, min2) = projection(polygon2, &axis);
+
+        // a segment projects onto its own normal as a point, so zero overlap still counts
+        if max1.min(max2) - min1.max(min2) < 0.0 {
+            return None;
+        }
+
+        // chooses the shortest way out, which also handles containment of one polygon in another
+        let (depth, normal) = if max1 - min2 < max2 - min1 {
+            (max1 - min2, axis)
+        } else {
+            (max2 - min1, axis.mul_n(-1.0))
+        };
+
+        match result {
+            Some((min_depth, _)) if depth >= min_depth => (),
+            _ => result = Some((depth, normal)),
+        }
+    }
+
+    result
+}
+
+/// Auxiliary function for finding the projection of a polygon on the axis
+///
+/// Returns: `(max, min)`
+fn projection(polygon: &[Vec2D], axis: &Vec2D) -> (f32, f32) {
+    let mut min = Vec2D::dot(axis, &polygon[0]);
+    let mut max = min;
+
+    for vertex in polygon {
+        let p = Vec2D::dot(axis, vertex);
+        min = min.min(p);
+        max = max.max(p);
+    }
+
+    (max, min)
+}
+
+/// Calculates the contact points of two overlapping convex polygons by clipping their edges
+///
+/// `axis` is the unit axis along which the first polygon has to be pushed to separate the polygons.
+///
+/// Returns: up to two pairs `(contact_vertex, overlap)`, the vertices lie on the incident edge and the overlaps are measured from the reference edge
+pub fn contact_points(polygon1: &[Vec2D], polygon2: &[Vec2D], axis: &Vec2D) -> Vec<(Vec2D, f32)> {
+    let reference2 = facing_edge(polygon2, axis);
+    let reference1 = facing_edge(polygon1, &axis.mul_n(-1.0));
+
+    // the edge that is more perpendicular to the axis is the reference one, the other polygon gives the incident edge
+    let (reference, incident_polygon) = match (reference1, reference2) {
+        (Some(reference1), Some(reference2)) if reference1.2 > reference2.2 + 0.001 => {
+            (reference1, polygon2)
+        }
+        (_, Some(reference2)) => (reference2, polygon1),
+        (Some(reference1), None) => (reference1, polygon2),
+        (None, None) => return Vec::new(),
+    };
+    let (first_point, second_point, _, reference_normal) = reference;
+    let incident = match facing_edge(incident_polygon, &reference_normal.mul_n(-1.0)) {
+        Some(incident) => incident,
+        None => return Vec::new(),
+    };
+
+    // clips the incident edge by the side planes of the reference edge
+    let tangent = (second_point - first_point).unit();
+    let mut clipped = vec![incident.0, incident.1];
+    for (offset, side) in [
+        (Vec2D::dot(&tangent, &first_point), 1.0),
+        (Vec2D::dot(&tangent, &second_point), -1.0),
+    ] {
+        clipped = clip_segment(&clipped, &tangent.mul_n(side), offset * side);
+        if clipped.len() < 2 {
+            return Vec::new();
+        }
+    }
+
+    // only the points behind the reference edge are in contact
+    clipped
+        .into_iter()
+        .map(|point| {
+            (
+                point,
+                -Vec2D::dot(&(point - first_point), &reference_normal),
+            )
+        })
+        .filter(|(_, overlap)| *overlap >= 0.0)
+        .collect()
+}
+
+/// Auxiliary function for finding the edge of a convex polygon whose outward normal is the closest to the direction
+///
+/// Returns: `(first_point, second_point, alignment, outward_normal)`
+fn facing_edge(polygon: &[Vec2D], direction: &Vec2D) -> Option<(Vec2D, Vec2D, f32, Vec2D)> {
+    let center = polygon
+        .iter()
+        .fold(Vec2D::default(), |sum, vertex| sum + *vertex)
+        .mul_n(1.0 / polygon.len() as f32);
+
+    let mut result: Option<(Vec2D, Vec2D, f32, Vec2D)> = None;
+    for (first_point, second_point) in edges(polygon) {
+        let mut normal = (second_point - first_point).unit().normal();
+
+        // a segment faces both ways, otherwise the normal looks away from the center
+        let outward = if polygon.len() == 2 {
+            *direction
+        } else {
+            first_point - center
+        };
+        if Vec2D::dot(&normal, &outward) < 0.0 {
+            normal = normal.mul_n(-1.0);
+        }
+
+        let alignment = Vec2D::dot(&normal, direction);
+        match result {
+            Some((_, _, max_alignment, _)) if alignment <= max_alignment => (),
+            _ => result = Some((first_point, second_point, alignment, normal)),
+        }
+    }
+
+    result
+}
+
+/// Auxiliary function that keeps the part of the segment where `dot(normal, point) >= offset`
+fn clip_segment(segment: &[Vec2D], normal: &Vec2D, offset: f32) -> Vec<Vec2D> {
+    let distance1 = Vec2D::dot(normal, &segment[0]) - offset;
+    let distance2 = Vec2D::dot(normal, &segment[1]) - offset;
+
+    let mut clipped = Vec::with_capacity(2);
+    if distance1 >= 0.0 {
+        clipped.push(segment[0]);
+    }
+    if distance2 >= 0.0 {
+        clipped.push(segment[1]);
+    }
+
+    // the segment crosses the plane, so the crossing point replaces the cut off end
+    if distance1 * distance2 < 0.0 {
+        let t = distance1 / (distance1 - distance2);
+        clipped.push(segment[0] + (segment[1] - segment[0]).mul_n(t));
+    }
+
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::objects::{line::Line, rectangle::Rectangle};
+
+    /// Auxiliary function that creates a non-moving box between the left top and the right bottom points
+    fn rectangle(plt: Vec2D, prb: Vec2D) -> Rectangle {
+        Rectangle::new(
+            Vec2D::new(plt.x, prb.y),
+            prb,
+            prb.y - plt.y,
+            0.0,
+            0.0,
+            Vec2D::default(),
+            0.0,
+            0.0,
+            0.0,
+        )
+    }
+
+    /// Auxiliary function that checks if two vectors are the same within the rounding error
+    fn assert_near(vector1: Vec2D, vector2: Vec2D) {
+        assert!(vector1.len_vector(&vector2) < 0.001, "{:?} != {:?}", vector1, vector2);
+    }
+
+    #[test]
+    fn distance_of_separated_touching_and_overlapping_objects() {
+        let left = rectangle(Vec2D::new(0.0, 0.0), Vec2D::new(10.0, 10.0));
+
+        let (gap, point1, point2) = distance(&left, &rectangle(Vec2D::new(15.0, 2.0), Vec2D::new(25.0, 8.0)));
+        assert!((gap - 5.0).abs() < 0.001);
+        assert!((point1.x - 10.0).abs() < 0.001 && (point2.x - 15.0).abs() < 0.001);
+
+        // touching objects are found by the overlap check, the distance is zero
+        let (gap, _, _) = distance(&left, &rectangle(Vec2D::new(10.0, 2.0), Vec2D::new(20.0, 8.0)));
+        assert!(gap.abs() < 0.001);
+
+        let (gap, point1, point2) = distance(&left, &rectangle(Vec2D::new(8.0, 2.0), Vec2D::new(18.0, 8.0)));
+        assert!((gap + 2.0).abs() < 0.001);
+        assert!((point1.len_vector(&point2) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn cast_ray_returns_the_first_hit() {
+        let square = [
+            Vec2D::new(0.0, 0.0),
+            Vec2D::new(10.0, 0.0),
+            Vec2D::new(10.0, 10.0),
+            Vec2D::new(0.0, 10.0),
+        ];
+
+        let (fraction, point, normal) =
+            cast_ray(&square, &Vec2D::new(-10.0, 5.0), &Vec2D::new(10.0, 5.0)).unwrap();
+        assert!((fraction - 0.5).abs() < 0.001);
+        assert_near(point, Vec2D::new(0.0, 5.0));
+        assert_near(normal, Vec2D::new(-1.0, 0.0));
+
+        // the normal faces the origin from whichever side the ray comes
+        let (fraction, _, normal) =
+            cast_ray(&square, &Vec2D::new(5.0, 30.0), &Vec2D::new(5.0, -10.0)).unwrap();
+        assert!((fraction - 0.5).abs() < 0.001);
+        assert_near(normal, Vec2D::new(0.0, 1.0));
+
+        assert!(cast_ray(&square, &Vec2D::new(-10.0, 5.0), &Vec2D::new(-1.0, 5.0)).is_none());
+        assert!(cast_ray(&square, &Vec2D::new(-10.0, 20.0), &Vec2D::new(20.0, 20.0)).is_none());
+    }
+
+    #[test]
+    fn shape_cast_stops_the_polygon_at_the_object() {
+        // a vertex of the polygon hits the edge of the object
+        let floor = Line::new(
+            Vec2D::new(-50.0, 0.0),
+            Vec2D::new(50.0, 0.0),
+            0.0,
+            1.0,
+            Vec2D::default(),
+            0.0,
+            0.0,
+            0.0,
+        );
+        let square = [
+            Vec2D::new(0.0, -20.0),
+            Vec2D::new(10.0, -20.0),
+            Vec2D::new(10.0, -10.0),
+            Vec2D::new(0.0, -10.0),
+        ];
+        let (fraction, _, normal) = shape_cast(&square, &Vec2D::new(0.0, 20.0), &floor).unwrap();
+        assert!((fraction - 0.5).abs() < 0.001);
+        assert_near(normal, Vec2D::new(0.0, -1.0));
+
+        // a vertex of the object hits the edge of the polygon
+        let post = rectangle(Vec2D::new(0.0, 0.0), Vec2D::new(10.0, 10.0));
+        let plank = [Vec2D::new(-50.0, -10.0), Vec2D::new(50.0, -10.0)];
+        let (fraction, point, normal) = shape_cast(&plank, &Vec2D::new(0.0, 20.0), &post).unwrap();
+        assert!((fraction - 0.5).abs() < 0.001);
+        assert!(point.y.abs() < 0.001);
+        assert_near(normal, Vec2D::new(0.0, -1.0));
+
+        assert!(shape_cast(&square, &Vec2D::new(0.0, -20.0), &floor).is_none());
+    }
+
+    #[test]
+    fn point_contact_pushes_the_point_out_of_the_object() {
+        // a point inside a polygon leaves through the nearest edge for either order of the vertices
+        let mut square = vec![
+            Vec2D::new(0.0, 0.0),
+            Vec2D::new(10.0, 0.0),
+            Vec2D::new(10.0, 10.0),
+            Vec2D::new(0.0, 10.0),
+        ];
+        for _ in 0..2 {
+            let contact = point_contact(Vec2D::new(3.0, 5.0), Vec2D::new(3.0, 5.0), &square).unwrap();
+            assert_near(contact.position, Vec2D::new(0.0, 5.0));
+            assert_near(contact.normal, Vec2D::new(-1.0, 0.0));
+            square.reverse();
+        }
+        assert!(point_contact(Vec2D::new(13.0, 5.0), Vec2D::new(3.0, 5.0), &square).is_none());
+
+        // a point that crossed a segment is returned to the side it came from
+        let floor = [Vec2D::new(-50.0, 0.0), Vec2D::new(50.0, 0.0)];
+        let contact = point_contact(Vec2D::new(0.0, 5.0), Vec2D::new(0.0, -5.0), &floor).unwrap();
+        assert_near(contact.point, Vec2D::new(0.0, 0.0));
+        assert_near(contact.normal, Vec2D::new(0.0, -1.0));
+        assert!(contact.position.y < 0.0);
+        assert!(point_contact(Vec2D::new(0.0, 5.0), Vec2D::new(0.0, 1.0), &floor).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+use super::{
+    joints::{revolute::RevoluteJoint, JointHandle},
+    map::Map,
+    objects::rectangle::Rectangle,
+    solver::ObjectId,
+    spring::Spring,
+    traits::joint_interface::JointInterface,
+    types::vec2d::Vec2D,
+};
+
+/// Objects and joints of a rope built on a map
+#[derive(Debug, Clone, Default)]
+pub struct Rope {
+    /// Indices of the segments in the moving objects of the map, from the start of the rope to its end
+    pub segments: Vec<usize>,
+    /// Handles of the joints, from the joint at the start of the rope to the joint at its end
+    pub joints: Vec<JointHandle>,
+    /// Indices of the bending springs in the springs of the map, empty for a rope that bends freely
+    pub springs: Vec<usize>,
+}
+
+/// Structure that builds ropes and chains of rectangular segments linked by revolute joints
+///
+/// The ends of the rope are attached to the points of the map on any objects, e.g. on lines or moving objects, or on `ObjectId::World`.
+/// A stiff chain gets implicit springs between the centers of every other segment, which straighten it when it bends
+pub struct RopeBuilder {
+    start: (ObjectId, Vec2D),
+    end: (ObjectId, Vec2D),
+    segments: usize,
+    width: f32,
+    mass: f32,
+    stiffness: f32,
+    damping: f32,
+}
+
+impl RopeBuilder {
+    /// Creating a builder of a rope between two points of the map on the objects
+    pub fn new(start: (ObjectId, Vec2D), end: (ObjectId, Vec2D)) -> Self {
+        RopeBuilder {
+            start,
+            end,
+            segments: 10,
+            width: 4.0,
+            mass: 0.1,
+            stiffness: 0.0,
+            damping: 0.0,
+        }
+    }
+
+    pub fn set_segments(&mut self, segments: usize) {
+        self.segments = segments.max(1);
+    }
+
+    pub fn get_segments(&self) -> usize {
+        self.segments
+    }
+
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width;
+    }
+
+    pub fn get_width(&self) -> f32 {
+        self.width
+    }
+
+    /// Mass of one segment
+    pub fn set_mass(&mut self, mass: f32) {
+        self.mass = mass;
+    }
+
+    pub fn get_mass(&self) -> f32 {
+        self.mass
+    }
+
+    /// Stiffness of the bending springs, zero makes a rope that bends freely
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.stiffness = stiffness;
+    }
+
+    pub fn get_stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    /// Damping of the bending springs
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping;
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Adds the segments and the joints of the rope to the map
+    pub fn build(&self, map: &mut Map) -> Rope {
+        let mut rope = Rope::default();
+        let edge = (self.end.1 - self.start.1).mul_n(1.0 / self.segments as f32);
+
+        // the edge of a rectangle is shifted, so that the rope goes through the centers of the segments
+        let shift = edge.unit().normal().mul_n(self.width / 2.0);
+        for n in 0..self.segments {
+            let first_point = self.start.1 + edge.mul_n(n as f32);
+            rope.segments.push(map.dyn_objects.len());
+            map.dyn_objects.push(Box::new(Rectangle::new(
+                first_point + shift,
+                first_point + edge + shift,
+                self.width,
+                self.mass,
+                0.0,
+                Vec2D::default(),
+                0.0,
+                0.0,
+                0.0,
+            )));
+        }
+
+        // links every segment with the previous one, the first segment is linked with the start object
+        let mut previous = self.start.0;
+        for (n, segment) in rope.segments.iter().enumerate() {
+            let point = self.start.1 + edge.mul_n(n as f32);
+            rope.joints.push(Rope::link(map, previous, ObjectId::Dynamic(*segment), point));
+            previous = ObjectId::Dynamic(*segment);
+        }
+        rope.joints.push(Rope::link(map, previous, self.end.0, self.end.1));
+
+        // the springs keep the distance between the centers of every other segment as in the straight rope
+        if self.stiffness > 0.0 {
+            for triple in rope.segments.windows(3) {
+                let mut spring = Spring::new(
+                    ObjectId::Dynamic(triple[0]),
+                    ObjectId::Dynamic(triple[2]),
+                    Vec2D::default(),
+                    Vec2D::default(),
+                    edge.len_vector(&Vec2D::default()) * 2.0,
+                    self.stiffness,
+                    self.damping,
+                );
+                spring.set_implicit(true);
+                rope.springs.push(map.springs.len());
+                map.springs.push(spring);
+            }
+        }
+
+        rope
+    }
+}
+
+impl Rope {
+    /// Auxiliary function that pins two objects together at the point of the map
+    fn link(map: &mut Map, object1: ObjectId, object2: ObjectId, point: Vec2D) -> JointHandle {
+        let mut joint = RevoluteJoint::new(
+            object1,
+            object2,
+            map.get_local_point(object1, point),
+            map.get_local_point(object2, point),
+            map.get_relative_angle(object1, object2),
+        );
+        // neighbouring segments overlap at the links, so they must not push each other apart
+        joint.set_collide_connected(false);
+
+        map.add_joint(Box::new(joint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        joints::mouse::MouseJoint,
+        test_utils::{map_with_gravity, square},
+    };
+
+    #[test]
+    fn slack_rope_sags_between_its_ends() {
+        // the rope of 300 is built straight to a small box, which is dragged to 200 from the start
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(602.0, 300.0), 4.0, 0.1)));
+        let rope = RopeBuilder::new(
+            (ObjectId::World, Vec2D::new(300.0, 300.0)),
+            (ObjectId::Dynamic(0), Vec2D::new(600.0, 300.0)),
+        )
+        .build(&mut map);
+        map.add_joint(Box::new(MouseJoint::new(
+            ObjectId::Dynamic(0),
+            Vec2D::new(-2.0, 0.0),
+            Vec2D::new(500.0, 300.0),
+            1.0e5,
+        )));
+        for _ in 0..600 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the chain hangs symmetrically, the catenary of the same length drops by about 105 in the middle
+        let sags: Vec<f32> = rope
+            .segments
+            .iter()
+            .map(|segment| map.dyn_objects[*segment].get_current_position().y - 300.0)
+            .collect();
+        for n in 0..sags.len() / 2 {
+            assert!((sags[n] - sags[sags.len() - 1 - n]).abs() < 10.0, "{:?}", sags);
+            assert!(sags[n] < sags[n + 1] + 1.0, "{:?}", sags);
+        }
+        assert!((85.0..110.0).contains(&sags[sags.len() / 2]), "{:?}", sags);
+    }
+}
@@ -0,0 +1,689 @@
+use super::{
+    query::{point_contact, signed_area},
+    traits::{
+        force_field_interface::ForceFieldInterface, move_interface::MoveInterface,
+        object_interface::ObjectInterface,
+    },
+    types::{angle::Angle, material::Material, matrix2d::Matrix2D, vec2d::Vec2D},
+};
+
+/// Point of a soft body that carries a part of its mass
+#[derive(Debug, Copy, Clone)]
+pub struct PointMass {
+    pub position: Vec2D,
+    pub velocity: Vec2D,
+    pub inversion_mass: f32,
+}
+
+/// Squishy object made of point masses connected by damped springs
+///
+/// The points on the outer hull collide with the objects of the map. The pressure inflates the hull like a balloon,
+/// shape matching pulls the points to the rest shape turned and moved with the body
+pub struct SoftBody {
+    points: Vec<PointMass>,
+    springs: Vec<(usize, usize, f32)>,
+    hull: Vec<usize>,
+    rest_shape: Vec<Vec2D>,
+    rest_area: f32,
+    stiffness: f32,
+    damping: f32,
+    pressure: f32,
+    shape_matching: f32,
+    material: Material,
+    iterations: usize,
+}
+
+impl SoftBody {
+    /// Creating a soft body from the outline, the vertices go around its perimeter and become the points of the hull
+    ///
+    /// Every point is connected with its neighbours and the points next to them, the mass is spread evenly over the points
+    pub fn new(outline: Vec<Vec2D>, mass: f32, stiffness: f32, damping: f32) -> Self {
+        let count = outline.len();
+        let mut soft_body = SoftBody::from_points(outline, mass, stiffness, damping);
+        for i in 0..count {
+            for j in [(i + 1) % count, (i + 2) % count] {
+                // short outlines reach the same pair from both ends, e.g. the diagonals of a square
+                if j != i && !soft_body.has_spring(i, j) {
+                    soft_body.add_spring(i, j);
+                }
+            }
+        }
+        soft_body.hull = (0..count).collect();
+        soft_body.rest_area = soft_body.get_area();
+
+        soft_body
+    }
+
+    /// Creating a soft body in the form of a grid of points between the left top and the right bottom points, with diagonal springs in every cell
+    pub fn from_grid(
+        plt: Vec2D,
+        prb: Vec2D,
+        columns: usize,
+        rows: usize,
+        mass: f32,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let cell = Vec2D::new(
+            (prb.x - plt.x) / columns as f32,
+            (prb.y - plt.y) / rows as f32,
+        );
+        let index = |column: usize, row: usize| row * (columns + 1) + column;
+
+        let mut points = Vec::<Vec2D>::with_capacity((columns + 1) * (rows + 1));
+        for row in 0..=rows {
+            for column in 0..=columns {
+                points.push(plt + Vec2D::new(cell.x * column as f32, cell.y * row as f32));
+            }
+        }
+
+        let mut soft_body = SoftBody::from_points(points, mass, stiffness, damping);
+        for row in 0..=rows {
+            for column in 0..=columns {
+                if column < columns {
+                    soft_body.add_spring(index(column, row), index(column + 1, row));
+                }
+                if row < rows {
+                    soft_body.add_spring(index(column, row), index(column, row + 1));
+                }
+                if column < columns && row < rows {
+                    soft_body.add_spring(index(column, row), index(column + 1, row + 1));
+                    soft_body.add_spring(index(column + 1, row), index(column, row + 1));
+                }
+            }
+        }
+
+        // the hull goes around the grid: the top row, the right column, the bottom row and the left column
+        soft_body.hull = (0..columns)
+            .map(|column| index(column, 0))
+            .chain((0..rows).map(|row| index(columns, row)))
+            .chain((1..=columns).rev().map(|column| index(column, rows)))
+            .chain((1..=rows).rev().map(|row| index(0, row)))
+            .collect();
+        soft_body.rest_area = soft_body.get_area();
+
+        soft_body
+    }
+
+    /// Auxiliary function that creates a soft body from the points without springs and hull
+    fn from_points(points: Vec<Vec2D>, mass: f32, stiffness: f32, damping: f32) -> Self {
+        let inversion_mass = if mass == 0.0 || points.is_empty() {
+            0.0
+        } else {
+            points.len() as f32 / mass
+        };
+        let centroid = SoftBody::get_mean(&points);
+
+        SoftBody {
+            rest_shape: points.iter().map(|point| *point - centroid).collect(),
+            points: points
+                .into_iter()
+                .map(|position| PointMass {
+                    position,
+                    velocity: Vec2D::default(),
+                    inversion_mass,
+                })
+                .collect(),
+            springs: Vec::<(usize, usize, f32)>::new(),
+            hull: Vec::<usize>::new(),
+            rest_area: 0.0,
+            stiffness,
+            damping,
+            pressure: 0.0,
+            shape_matching: 0.0,
+            material: Material::default(),
+            iterations: 8,
+        }
+    }
+
+    /// Connects two points with a spring, the current distance between them becomes its rest length
+    pub fn add_spring(&mut self, point1: usize, point2: usize) {
+        let rest_length = self.points[point1]
+            .position
+            .len_vector(&self.points[point2].position);
+        self.springs.push((point1, point2, rest_length));
+    }
+
+    pub fn get_springs(&self) -> &[(usize, usize, f32)] {
+        &self.springs
+    }
+
+    pub fn get_points(&self) -> &[PointMass] {
+        &self.points
+    }
+
+    pub fn get_mut_points(&mut self) -> &mut [PointMass] {
+        &mut self.points
+    }
+
+    /// Indices of the points of the outer hull, going around the perimeter
+    pub fn get_hull(&self) -> &[usize] {
+        &self.hull
+    }
+
+    /// Returns the positions of the points of the outer hull
+    pub fn get_outline(&self) -> Vec<Vec2D> {
+        self.hull.iter().map(|i| self.points[*i].position).collect()
+    }
+
+    /// Returns the area inside the outer hull
+    pub fn get_area(&self) -> f32 {
+        signed_area(&self.get_outline()).abs()
+    }
+
+    /// Returns the center of the mass of the points
+    pub fn get_centroid(&self) -> Vec2D {
+        SoftBody::get_mean(
+            &self
+                .points
+                .iter()
+                .map(|point| point.position)
+                .collect::<Vec<Vec2D>>(),
+        )
+    }
+
+    /// Sets the velocity of all points
+    pub fn set_velocity(&mut self, velocity: Vec2D) {
+        for point in self.points.iter_mut() {
+            point.velocity = velocity;
+        }
+    }
+
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.stiffness = stiffness;
+    }
+
+    pub fn get_stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping;
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Pressure of the gas inside the hull at the rest area, zero disables the pressure
+    pub fn set_pressure(&mut self, pressure: f32) {
+        self.pressure = pressure;
+    }
+
+    pub fn get_pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    /// Part of the way to the rest shape that the points pass at every iteration, from zero (disabled) to one (rigid)
+    pub fn set_shape_matching(&mut self, shape_matching: f32) {
+        self.shape_matching = shape_matching.clamp(0.0, 1.0);
+    }
+
+    pub fn get_shape_matching(&self) -> f32 {
+        self.shape_matching
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    pub fn get_material(&self) -> Material {
+        self.material
+    }
+
+    /// Number of iterations into which the step is split, stiff springs need more iterations to stay stable
+    pub fn set_iterations(&mut self, iterations: usize) {
+        self.iterations = iterations.max(1);
+    }
+
+    pub fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Function moves the points and resolves the collisions of the hull with the objects of the map
+    ///
+    /// Moving objects get the opposite impulses of the collisions
+    pub fn run(
+        &mut self,
+        time: f32,
+        gravity: Vec2D,
+        force_fields: &[Box<dyn ForceFieldInterface>],
+        objects: &[Box<dyn ObjectInterface>],
+        dyn_objects: &mut [Box<dyn MoveInterface>],
+    ) {
+        let time = time / self.iterations as f32;
+        for _ in 0..self.iterations {
+            let forces = self.get_forces(gravity, force_fields);
+
+            let previous: Vec<Vec2D> = self.points.iter().map(|point| point.position).collect();
+            for (point, force) in self.points.iter_mut().zip(forces) {
+                point.velocity += force.mul_n(point.inversion_mass * time);
+                point.position += point.velocity.mul_n(time);
+            }
+
+            if self.shape_matching > 0.0 {
+                self.match_shape(time);
+            }
+
+            // only the hull touches other objects
+            for n in 0..self.hull.len() {
+                let i = self.hull[n];
+                for object in objects {
+                    self.collide(i, previous[i], object.as_ref(), None);
+                }
+
+                for dyn_object in dyn_objects.iter_mut() {
+                    if let Some((impulse, point)) =
+                        self.collide(i, previous[i], dyn_object.as_object(), Some(&**dyn_object))
+                    {
+                        dyn_object.apply_impulse_at_point(impulse.mul_n(-1.0), point);
+                    }
+                }
+            }
+
+            // the corners of the objects are checked against the edges of the hull, so that they don't pass between the points
+            for object in objects {
+                self.collide_edges(object.as_ref(), None);
+            }
+
+            for dyn_object in dyn_objects.iter_mut() {
+                for (impulse, point) in self.collide_edges(dyn_object.as_object(), Some(&**dyn_object)) {
+                    dyn_object.apply_impulse_at_point(impulse.mul_n(-1.0), point);
+                }
+            }
+        }
+    }
+
+    /// Auxiliary function for calculating the forces of gravity, force fields, springs and pressure applied to the points
+    fn get_forces(
+        &self,
+        gravity: Vec2D,
+        force_fields: &[Box<dyn ForceFieldInterface>],
+    ) -> Vec<Vec2D> {
+        let mut forces: Vec<Vec2D> = self
+            .points
+            .iter()
+            .map(|point| {
+                if point.inversion_mass == 0.0 {
+                    return Vec2D::default();
+                }
+
+                let acceleration =
+                    force_fields
+                        .iter()
+                        .fold(gravity, |acceleration, force_field| {
+                            acceleration + force_field.get_acceleration_at(&point.position)
+                        });
+                acceleration.mul_n(1.0 / point.inversion_mass)
+            })
+            .collect();
+
+        // a stretched spring pulls its points together, the damping slows down their relative movement
+        for (point1, point2, rest_length) in &self.springs {
+            let distance = self.points[*point2].position - self.points[*point1].position;
+            let axis = distance.unit();
+            let velocity = Vec2D::dot(
+                &(self.points[*point2].velocity - self.points[*point1].velocity),
+                &axis,
+            );
+            let force = axis.mul_n(
+                self.stiffness * (distance.len_vector(&Vec2D::default()) - rest_length)
+                    + self.damping * velocity,
+            );
+            forces[*point1] += force;
+            forces[*point2] -= force;
+        }
+
+        // the pressure falls as the area grows and pushes every edge of the hull outwards
+        if self.pressure > 0.0 && self.hull.len() > 2 {
+            let outline = self.get_outline();
+            let area = signed_area(&outline);
+            if area != 0.0 {
+                let pressure = self.pressure * self.rest_area / area.abs();
+                for n in 0..self.hull.len() {
+                    let edge = outline[(n + 1) % outline.len()] - outline[n];
+
+                    // the normal of an edge looks inside the hull going clockwise on the screen
+                    let force = edge.normal().mul_n(-area.signum() * pressure / 2.0);
+                    forces[self.hull[n]] += force;
+                    forces[self.hull[(n + 1) % self.hull.len()]] += force;
+                }
+            }
+        }
+
+        forces
+    }
+
+    /// Auxiliary function that pulls the points to the rest shape moved to the centroid and turned by the mean turn of the points
+    fn match_shape(&mut self, time: f32) {
+        let centroid = self.get_centroid();
+        let (cos, sin) = self.points.iter().zip(&self.rest_shape).fold(
+            (0.0, 0.0),
+            |(cos, sin), (point, rest)| {
+                let offset = point.position - centroid;
+                (
+                    cos + Vec2D::dot(rest, &offset),
+                    sin + Vec2D::cross(rest, &offset),
+                )
+            },
+        );
+        let rotation_matrix = Matrix2D::rotation_matrix(&Angle::new(sin.atan2(cos)));
+
+        for (point, rest) in self.points.iter_mut().zip(&self.rest_shape) {
+            if point.inversion_mass == 0.0 {
+                continue;
+            }
+
+            let goal = centroid + rotation_matrix.multiply_vec2d(rest);
+            let shift = (goal - point.position).mul_n(self.shape_matching);
+            point.position += shift;
+            point.velocity += shift.mul_n(1.0 / time);
+        }
+    }
+
+    /// Auxiliary function that pushes the point out of the object and removes the velocity with which it moves into it
+    ///
+    /// The moving object is passed to take its velocity into account. Returns the impulse given to the point and the point of the contact
+    fn collide(
+        &mut self,
+        i: usize,
+        previous: Vec2D,
+        object: &dyn ObjectInterface,
+        dyn_object: Option<&dyn MoveInterface>,
+    ) -> Option<(Vec2D, Vec2D)> {
+        let contact = point_contact(
+            self.points[i].position,
+            previous,
+            &object.get_potential_vertex(),
+        )?;
+        let point = &mut self.points[i];
+        point.position = contact.position;
+
+        let (arm, inversion_mass, inversion_inertia, velocity) =
+            SoftBody::get_motion_at(dyn_object, contact.point);
+        let relative_velocity = point.velocity - velocity;
+        if Vec2D::dot(&relative_velocity, &contact.normal) >= 0.0 {
+            return None;
+        }
+
+        let friction = self
+            .material
+            .combine(&object.get_material())
+            .get_dynamic_friction();
+        let impulse = SoftBody::get_impulse(
+            &relative_velocity,
+            &contact.normal,
+            friction,
+            point.inversion_mass,
+            &arm,
+            inversion_mass,
+            inversion_inertia,
+        );
+        point.velocity += impulse.mul_n(point.inversion_mass);
+
+        Some((impulse, contact.point))
+    }
+
+    /// Auxiliary function that pushes the edges of the hull out of the vertices of the object and removes the velocity with which they move into them
+    ///
+    /// The moving object is passed to take its velocity into account. Returns the impulses given to the edges and the vertices where they are applied
+    fn collide_edges(
+        &mut self,
+        object: &dyn ObjectInterface,
+        dyn_object: Option<&dyn MoveInterface>,
+    ) -> Vec<(Vec2D, Vec2D)> {
+        let mut impulses = Vec::<(Vec2D, Vec2D)>::new();
+        if self.hull.len() < 3 {
+            return impulses;
+        }
+
+        for vertex in object.get_potential_vertex() {
+            let outline = self.get_outline();
+            let Some((n, along, depth)) = SoftBody::get_nearest_edge(&outline, vertex) else {
+                continue;
+            };
+            let (i, k) = (self.hull[n], self.hull[(n + 1) % self.hull.len()]);
+
+            // the normal of an edge looks inside the hull going clockwise on the screen, the edge is pushed there
+            let normal = (outline[(n + 1) % outline.len()] - outline[n])
+                .normal()
+                .unit()
+                .mul_n(signed_area(&outline).signum());
+
+            // the points of the edge are moved in proportion to their share of the nearest point and their inversion masses
+            let inversion_mass1 = self.points[i].inversion_mass * (1.0 - along);
+            let inversion_mass2 = self.points[k].inversion_mass * along;
+            let edge_inversion_mass = inversion_mass1 * (1.0 - along) + inversion_mass2 * along;
+            if edge_inversion_mass == 0.0 {
+                continue;
+            }
+            self.points[i].position += normal.mul_n(depth * inversion_mass1 / edge_inversion_mass);
+            self.points[k].position += normal.mul_n(depth * inversion_mass2 / edge_inversion_mass);
+
+            let (arm, inversion_mass, inversion_inertia, velocity) =
+                SoftBody::get_motion_at(dyn_object, vertex);
+            let relative_velocity = self.points[i].velocity.mul_n(1.0 - along)
+                + self.points[k].velocity.mul_n(along)
+                - velocity;
+            if Vec2D::dot(&relative_velocity, &normal) >= 0.0 {
+                continue;
+            }
+
+            let friction = self
+                .material
+                .combine(&object.get_material())
+                .get_dynamic_friction();
+            let impulse = SoftBody::get_impulse(
+                &relative_velocity,
+                &normal,
+                friction,
+                edge_inversion_mass,
+                &arm,
+                inversion_mass,
+                inversion_inertia,
+            );
+            self.points[i].velocity += impulse.mul_n(inversion_mass1);
+            self.points[k].velocity += impulse.mul_n(inversion_mass2);
+            impulses.push((impulse, vertex));
+        }
+
+        impulses
+    }
+
+    /// Auxiliary function for finding the edge of the outline nearest to the point inside it
+    ///
+    /// Returns: `None` - if the point is outside the outline or
+    ///
+    /// `Some((n, along, depth))` - the index of the first point of the edge, the part of the edge before the nearest point and the distance to it
+    fn get_nearest_edge(outline: &[Vec2D], point: Vec2D) -> Option<(usize, f32, f32)> {
+        // the point is inside if a ray from it to the right crosses the outline an odd number of times
+        let mut inside = false;
+        let mut nearest: Option<(usize, f32, f32)> = None;
+        for n in 0..outline.len() {
+            let (first, second) = (outline[n], outline[(n + 1) % outline.len()]);
+            if (first.y > point.y) != (second.y > point.y)
+                && point.x < first.x + (point.y - first.y) * (second.x - first.x) / (second.y - first.y)
+            {
+                inside = !inside;
+            }
+
+            let edge = second - first;
+            let length = Vec2D::dot(&edge, &edge);
+            if length == 0.0 {
+                continue;
+            }
+            let along = (Vec2D::dot(&(point - first), &edge) / length).clamp(0.0, 1.0);
+            let depth = (first + edge.mul_n(along)).len_vector(&point);
+            if nearest.is_none_or(|(_, _, min_depth)| depth < min_depth) {
+                nearest = Some((n, along, depth));
+            }
+        }
+
+        nearest.filter(|_| inside)
+    }
+
+    /// Auxiliary function that returns the arm, the inversion mass, the inversion inertia and the velocity of the moving object at the point
+    fn get_motion_at(dyn_object: Option<&dyn MoveInterface>, point: Vec2D) -> (Vec2D, f32, f32, Vec2D) {
+        match dyn_object {
+            Some(dyn_object) => {
+                let arm = point - dyn_object.get_current_position();
+                (
+                    arm,
+                    dyn_object.get_inversion_mass(),
+                    dyn_object.get_inversion_inertia(),
+                    dyn_object.get_velocity() + arm.normal().mul_n(dyn_object.get_angle_velocity()),
+                )
+            }
+            None => (Vec2D::default(), 0.0, 0.0, Vec2D::default()),
+        }
+    }
+
+    /// Auxiliary function for calculating the impulse that stops the point of the soft body moving into the object along the normal
+    ///
+    /// The point slides along the object with the dynamic friction. A point and an object that both can't move get no impulse
+    fn get_impulse(
+        relative_velocity: &Vec2D,
+        normal: &Vec2D,
+        friction: f32,
+        point_inversion_mass: f32,
+        arm: &Vec2D,
+        inversion_mass: f32,
+        inversion_inertia: f32,
+    ) -> Vec2D {
+        let get_mass = |axis: &Vec2D| {
+            let impulse_augmentation = Vec2D::cross(arm, axis);
+            let inversion_mass = point_inversion_mass
+                + inversion_mass
+                + inversion_inertia * impulse_augmentation * impulse_augmentation;
+            if inversion_mass == 0.0 {
+                0.0
+            } else {
+                1.0 / inversion_mass
+            }
+        };
+
+        let normal_impulse = -Vec2D::dot(relative_velocity, normal) * get_mass(normal);
+        let tangent = normal.normal();
+        let max_friction = friction * normal_impulse;
+        let friction_impulse = (-Vec2D::dot(relative_velocity, &tangent) * get_mass(&tangent))
+            .clamp(-max_friction, max_friction);
+
+        normal.mul_n(normal_impulse) + tangent.mul_n(friction_impulse)
+    }
+
+    /// Auxiliary function for calculating the mean of the points
+    fn get_mean(points: &[Vec2D]) -> Vec2D {
+        if points.is_empty() {
+            return Vec2D::default();
+        }
+
+        points
+            .iter()
+            .fold(Vec2D::default(), |sum, point| sum + *point)
+            .mul_n(1.0 / points.len() as f32)
+    }
+
+    /// Auxiliary function that checks if two points are already connected by a spring
+    fn has_spring(&self, point1: usize, point2: usize) -> bool {
+        self.springs
+            .iter()
+            .any(|&(i, j, _)| (i, j) == (point1, point2) || (i, j) == (point2, point1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        map::Map,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that runs the map for the seconds
+    fn run(map: &mut Map, seconds: usize) {
+        for _ in 0..seconds * 60 {
+            map.run(1.0 / 60.0);
+        }
+    }
+
+    #[test]
+    fn soft_body_rests_on_the_floor_and_keeps_its_area() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        let mut soft_body = SoftBody::from_grid(
+            Vec2D::new(400.0, 800.0),
+            Vec2D::new(500.0, 900.0),
+            4,
+            4,
+            10.0,
+            500.0,
+            20.0,
+        );
+        soft_body.set_pressure(50.0);
+        map.soft_bodies.push(soft_body);
+        run(&mut map, 5);
+
+        // the lowest points lie on the floor, the pressure holds the hull near its rest area against the gravity
+        let soft_body = &map.soft_bodies[0];
+        let bottom = soft_body
+            .get_points()
+            .iter()
+            .fold(0.0_f32, |bottom, point| bottom.max(point.position.y));
+        let speed = soft_body
+            .get_points()
+            .iter()
+            .fold(0.0_f32, |speed, point| speed.max(point.velocity.len_vector(&Vec2D::default())));
+        assert!((bottom - 1000.0).abs() < 0.1, "{}", bottom);
+        assert!(speed < 5.0, "{}", speed);
+        assert!((soft_body.get_area() - 10000.0).abs() < 1000.0, "{}", soft_body.get_area());
+    }
+
+    #[test]
+    fn corner_of_a_box_does_not_pass_through_the_hull() {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        let soft_body = SoftBody::new(
+            vec![
+                Vec2D::new(400.0, 880.0),
+                Vec2D::new(600.0, 880.0),
+                Vec2D::new(600.0, 980.0),
+                Vec2D::new(400.0, 980.0),
+            ],
+            10.0,
+            500.0,
+            5.0,
+        );
+        map.soft_bodies.push(soft_body);
+        map.dyn_objects.push(Box::new(square(Vec2D::new(500.0, 800.0), 10.0, 1.0)));
+        run(&mut map, 5);
+
+        // no hull point gets inside the small box, only its corners can touch the top edge
+        let outline = map.soft_bodies[0].get_outline();
+        for vertex in map.dyn_objects[0].get_potential_vertex() {
+            assert!(SoftBody::get_nearest_edge(&outline, vertex).is_none_or(|(_, _, depth)| depth < 0.5));
+        }
+        assert!((map.dyn_objects[0].get_current_position().y - (outline[0].y - 5.0)).abs() < 1.0);
+    }
+
+    #[test]
+    fn soft_body_without_mass_stays_finite_on_the_floor() {
+        // neither the points nor the floor can be moved by the collision, so there is no impulse
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        let mut soft_body = SoftBody::from_grid(
+            Vec2D::new(400.0, 980.0),
+            Vec2D::new(420.0, 1000.0),
+            1,
+            1,
+            0.0,
+            500.0,
+            5.0,
+        );
+        soft_body.set_velocity(Vec2D::new(0.0, 100.0));
+        map.soft_bodies.push(soft_body);
+        run(&mut map, 1);
+
+        for point in map.soft_bodies[0].get_points() {
+            assert!(point.position.y.is_finite() && point.velocity.y.is_finite());
+        }
+    }
+}
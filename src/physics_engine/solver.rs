@@ -0,0 +1,313 @@
+use std::thread;
+
+use super::{
+    island::Island,
+    traits::{constraint_interface::ConstraintInterface, object_interface::ObjectInterface},
+    types::{angle::Angle, matrix2d::Matrix2D, vec2d::Vec2D},
+};
+
+/// Reference to an object of the map
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ObjectId {
+    /// Index of a non-moving object
+    Static(usize),
+    /// Index of a moving object
+    Dynamic(usize),
+    /// The map itself, a non-moving body at the origin, so anchors on it are points of the map
+    World,
+}
+
+/// Snapshot of the object characteristics that the solver changes
+#[derive(Debug, Copy, Clone)]
+pub struct SolverBody {
+    pub position: Vec2D,
+    pub angle: f32,
+    pub velocity: Vec2D,
+    pub angle_velocity: f32,
+    pub inversion_mass: f32,
+    pub inversion_inertia: f32,
+}
+
+impl SolverBody {
+    /// Creating a body from a moving object
+    pub fn new(object: &dyn ObjectInterface) -> Self {
+        SolverBody {
+            position: object.get_current_position(),
+            angle: object.get_angle().get_radian(),
+            velocity: object.get_velocity(),
+            angle_velocity: object.get_angle_velocity(),
+            inversion_mass: object.get_inversion_mass(),
+            inversion_inertia: object.get_inversion_inertia(),
+        }
+    }
+
+    /// Creating a body from a non-moving object, which can't be pushed by constraints
+    pub fn new_static(object: &dyn ObjectInterface) -> Self {
+        SolverBody {
+            inversion_mass: 0.0,
+            inversion_inertia: 0.0,
+            ..SolverBody::new(object)
+        }
+    }
+
+    /// Creating the body of the map itself, which has no mass and stays at the origin
+    pub fn new_world() -> Self {
+        SolverBody {
+            position: Vec2D::default(),
+            angle: 0.0,
+            velocity: Vec2D::default(),
+            angle_velocity: 0.0,
+            inversion_mass: 0.0,
+            inversion_inertia: 0.0,
+        }
+    }
+
+    /// Assigns the characteristics changed by the solver to the object
+    pub fn apply_to(&self, object: &mut dyn ObjectInterface) {
+        object.set_current_position(self.position);
+        object.set_angle(Angle::new(self.angle));
+        object.set_velocity(self.velocity);
+        object.set_angle_velocity(self.angle_velocity);
+    }
+
+    /// Calculates the position of the body after the time, as it will be moved by its velocity
+    pub fn get_position(&self, time: f32) -> Vec2D {
+        self.position + self.velocity.mul_n(time)
+    }
+
+    /// Calculates the arm of the point given relative to the body (at zero angle), the body is turned by its angle velocity for the time
+    pub fn get_arm(&self, local_point: &Vec2D, time: f32) -> Vec2D {
+        Matrix2D::rotation_matrix(&Angle::new(self.angle + self.angle_velocity * time))
+            .multiply_vec2d(local_point)
+    }
+
+    /// Calculates the velocity of the point of the body located at the arm from its center
+    pub fn velocity_at(&self, arm: &Vec2D) -> Vec2D {
+        self.velocity + Vec2D::new(-self.angle_velocity * arm.y, self.angle_velocity * arm.x)
+    }
+
+    /// Changes the velocities of the body by the impulse applied at the arm from its center
+    pub fn apply_impulse(&mut self, impulse: &Vec2D, arm: &Vec2D) {
+        self.velocity += impulse.mul_n(self.inversion_mass);
+        self.angle_velocity += self.inversion_inertia * Vec2D::cross(arm, impulse);
+    }
+
+    /// Changes the angle velocity of the body by the angular impulse
+    pub fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angle_velocity += self.inversion_inertia * impulse;
+    }
+
+    /// Turns the body by the angular positional impulse
+    pub fn apply_angular_displacement(&mut self, impulse: f32) {
+        self.angle += self.inversion_inertia * impulse;
+    }
+
+    /// Moves the body by the positional impulse applied at the arm from its center
+    pub fn apply_displacement(&mut self, impulse: &Vec2D, arm: &Vec2D) {
+        self.position += impulse.mul_n(self.inversion_mass);
+        self.angle += self.inversion_inertia * Vec2D::cross(arm, impulse);
+    }
+}
+
+/// Structure that resolves all constraints of a step together with sequential impulses
+pub struct Solver {
+    velocity_iterations: usize,
+    position_iterations: usize,
+    slop: f32,
+    joint_slop: f32,
+    correction_factor: f32,
+}
+
+impl Solver {
+    /// Creating a solver
+    ///
+    /// `slop` is the overlap of contacts that is left uncorrected, `correction_factor` is the fraction of the remaining error removed per position iteration
+    pub fn new(
+        velocity_iterations: usize,
+        position_iterations: usize,
+        slop: f32,
+        correction_factor: f32,
+    ) -> Self {
+        Solver {
+            velocity_iterations,
+            position_iterations,
+            slop,
+            joint_slop: 0.01,
+            correction_factor,
+        }
+    }
+
+    pub fn set_velocity_iterations(&mut self, velocity_iterations: usize) {
+        self.velocity_iterations = velocity_iterations;
+    }
+
+    pub fn get_velocity_iterations(&self) -> usize {
+        self.velocity_iterations
+    }
+
+    pub fn set_position_iterations(&mut self, position_iterations: usize) {
+        self.position_iterations = position_iterations;
+    }
+
+    pub fn get_position_iterations(&self) -> usize {
+        self.position_iterations
+    }
+
+    pub fn set_slop(&mut self, slop: f32) {
+        self.slop = slop;
+    }
+
+    pub fn get_slop(&self) -> f32 {
+        self.slop
+    }
+
+    /// Error of joints that is left uncorrected, it is much smaller than the slop of contacts, so that joints don't sag
+    pub fn set_joint_slop(&mut self, joint_slop: f32) {
+        self.joint_slop = joint_slop;
+    }
+
+    pub fn get_joint_slop(&self) -> f32 {
+        self.joint_slop
+    }
+
+    pub fn set_correction_factor(&mut self, correction_factor: f32) {
+        self.correction_factor = correction_factor;
+    }
+
+    pub fn get_correction_factor(&self) -> f32 {
+        self.correction_factor
+    }
+
+    /// Calculates the part of the overlap of a contact to remove in one position iteration
+    pub fn get_correction(&self, error: f32) -> f32 {
+        (error - self.slop).max(0.0) * self.correction_factor
+    }
+
+    /// Calculates the part of the error of a joint to remove in one position iteration
+    pub fn get_joint_correction(&self, error: f32) -> f32 {
+        (error - self.joint_slop).max(0.0) * self.correction_factor
+    }
+
+    /// Changes the bodies so that they satisfy the constraints
+    ///
+    /// `dyn_bodies` are the bodies of the moving objects with the indices `objects` in ascending order, `static_bodies` are indexed by `ObjectId::Static` and are never changed
+    pub fn solve(
+        &self,
+        objects: &[usize],
+        dyn_bodies: &mut [SolverBody],
+        static_bodies: &[SolverBody],
+        constraints: &mut [&mut dyn ConstraintInterface],
+        time: f32,
+    ) {
+        for constraint in constraints.iter_mut() {
+            let (object1, object2) = constraint.get_objects();
+            let (mut body1, mut body2) = (
+                Solver::get_body(objects, dyn_bodies, static_bodies, object1),
+                Solver::get_body(objects, dyn_bodies, static_bodies, object2),
+            );
+            constraint.prepare(&mut body1, &mut body2, time);
+            Solver::set_body(objects, dyn_bodies, object1, body1);
+            Solver::set_body(objects, dyn_bodies, object2, body2);
+        }
+
+        // every iteration brings the velocities closer to satisfying all constraints at once
+        for _ in 0..self.velocity_iterations {
+            for constraint in constraints.iter_mut() {
+                let (object1, object2) = constraint.get_objects();
+                let (mut body1, mut body2) = (
+                    Solver::get_body(objects, dyn_bodies, static_bodies, object1),
+                    Solver::get_body(objects, dyn_bodies, static_bodies, object2),
+                );
+                constraint.solve_velocity(&mut body1, &mut body2);
+                Solver::set_body(objects, dyn_bodies, object1, body1);
+                Solver::set_body(objects, dyn_bodies, object2, body2);
+            }
+        }
+
+        // removes the remaining errors by moving the bodies without changing their velocities
+        for _ in 0..self.position_iterations {
+            for constraint in constraints.iter_mut() {
+                let (object1, object2) = constraint.get_objects();
+                let (mut body1, mut body2) = (
+                    Solver::get_body(objects, dyn_bodies, static_bodies, object1),
+                    Solver::get_body(objects, dyn_bodies, static_bodies, object2),
+                );
+                constraint.solve_position(&mut body1, &mut body2, self);
+                Solver::set_body(objects, dyn_bodies, object1, body1);
+                Solver::set_body(objects, dyn_bodies, object2, body2);
+            }
+        }
+    }
+
+    /// Solves every island separately, the islands are divided between the threads
+    ///
+    /// Islands don't share moving bodies, so the result doesn't depend on the number of threads
+    pub fn solve_islands(
+        &self,
+        islands: &[Island],
+        island_bodies: &mut [Vec<SolverBody>],
+        static_bodies: &[SolverBody],
+        island_constraints: &mut [Vec<&mut dyn ConstraintInterface>],
+        time: f32,
+        threads: usize,
+    ) {
+        let chunk = islands.len().div_ceil(threads.max(1)).max(1);
+
+        // starting threads costs more than solving a single group of islands
+        if chunk >= islands.len() {
+            for ((island, bodies), constraints) in islands
+                .iter()
+                .zip(island_bodies.iter_mut())
+                .zip(island_constraints.iter_mut())
+            {
+                self.solve(&island.objects, bodies, static_bodies, constraints, time);
+            }
+            return;
+        }
+
+        thread::scope(|scope| {
+            for ((islands, island_bodies), island_constraints) in islands
+                .chunks(chunk)
+                .zip(island_bodies.chunks_mut(chunk))
+                .zip(island_constraints.chunks_mut(chunk))
+            {
+                scope.spawn(move || {
+                    for ((island, bodies), constraints) in islands
+                        .iter()
+                        .zip(island_bodies.iter_mut())
+                        .zip(island_constraints.iter_mut())
+                    {
+                        self.solve(&island.objects, bodies, static_bodies, constraints, time);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Auxiliary function that returns a copy of the body referenced by the object id
+    fn get_body(
+        objects: &[usize],
+        dyn_bodies: &[SolverBody],
+        static_bodies: &[SolverBody],
+        object: ObjectId,
+    ) -> SolverBody {
+        match object {
+            ObjectId::Static(i) => static_bodies[i],
+            ObjectId::Dynamic(i) => dyn_bodies[objects.binary_search(&i).unwrap()],
+            ObjectId::World => SolverBody::new_world(),
+        }
+    }
+
+    /// Auxiliary function that saves the body referenced by the object id, if it can be changed
+    fn set_body(objects: &[usize], dyn_bodies: &mut [SolverBody], object: ObjectId, body: SolverBody) {
+        if let ObjectId::Dynamic(i) = object {
+            dyn_bodies[objects.binary_search(&i).unwrap()] = body;
+        }
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new(8, 3, 0.5, 0.2)
+    }
+}
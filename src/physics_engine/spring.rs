@@ -0,0 +1,226 @@
+use super::{
+    joints::get_spring_softness,
+    solver::{ObjectId, Solver, SolverBody},
+    traits::constraint_interface::ConstraintInterface,
+    types::vec2d::Vec2D,
+};
+
+/// Damped spring between the anchors of two objects, or of an object and a point of the map (`ObjectId::World`)
+///
+/// An explicit spring adds its force to the objects before the integration. A stiff explicit spring overshoots and explodes,
+/// so an implicit spring is resolved by the solver as a soft constraint instead, which stays stable at any stiffness
+pub struct Spring {
+    object1: ObjectId,
+    object2: ObjectId,
+    local_anchor1: Vec2D,
+    local_anchor2: Vec2D,
+    rest_length: f32,
+    stiffness: f32,
+    damping: f32,
+    implicit: bool,
+    arm1: Vec2D,
+    arm2: Vec2D,
+    axis: Vec2D,
+    soft_mass: f32,
+    gamma: f32,
+    bias: f32,
+    impulse: f32,
+}
+
+impl Spring {
+    /// Creating an explicit spring
+    ///
+    /// Anchors are given relative to the centers of the objects at zero angle, the anchor on `ObjectId::World` is a point of the map
+    pub fn new(
+        object1: ObjectId,
+        object2: ObjectId,
+        local_anchor1: Vec2D,
+        local_anchor2: Vec2D,
+        rest_length: f32,
+        stiffness: f32,
+        damping: f32,
+    ) -> Self {
+        Spring {
+            object1,
+            object2,
+            local_anchor1,
+            local_anchor2,
+            rest_length,
+            stiffness,
+            damping,
+            implicit: false,
+            arm1: Vec2D::default(),
+            arm2: Vec2D::default(),
+            axis: Vec2D::default(),
+            soft_mass: 0.0,
+            gamma: 0.0,
+            bias: 0.0,
+            impulse: 0.0,
+        }
+    }
+
+    pub fn get_local_anchor1(&self) -> Vec2D {
+        self.local_anchor1
+    }
+
+    pub fn get_local_anchor2(&self) -> Vec2D {
+        self.local_anchor2
+    }
+
+    pub fn set_rest_length(&mut self, rest_length: f32) {
+        self.rest_length = rest_length;
+    }
+
+    pub fn get_rest_length(&self) -> f32 {
+        self.rest_length
+    }
+
+    pub fn set_stiffness(&mut self, stiffness: f32) {
+        self.stiffness = stiffness;
+    }
+
+    pub fn get_stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping;
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// An implicit spring is resolved by the solver instead of adding its force before the integration
+    pub fn set_implicit(&mut self, implicit: bool) {
+        self.implicit = implicit;
+        self.impulse = 0.0;
+    }
+
+    pub fn get_implicit(&self) -> bool {
+        self.implicit
+    }
+
+    /// Calculates the force of the spring applied to the second body, the first body gets the opposite force
+    ///
+    /// Returns the force and the anchors of the bodies on the map
+    pub fn get_force(&self, body1: &SolverBody, body2: &SolverBody) -> (Vec2D, Vec2D, Vec2D) {
+        let arm1 = body1.get_arm(&self.local_anchor1, 0.0);
+        let arm2 = body2.get_arm(&self.local_anchor2, 0.0);
+        let distance = body2.position + arm2 - body1.position - arm1;
+        let axis = distance.unit();
+
+        // a stretched spring pulls the anchors together, the damping slows down their relative movement
+        let stretch = distance.len_vector(&Vec2D::default()) - self.rest_length;
+        let velocity = Vec2D::dot(&(body2.velocity_at(&arm2) - body1.velocity_at(&arm1)), &axis);
+        let force = axis.mul_n(-self.stiffness * stretch - self.damping * velocity);
+
+        (force, body1.position + arm1, body2.position + arm2)
+    }
+
+    /// Auxiliary function for calculating the velocity with which the anchors move apart
+    fn get_separate_velocity(&self, body1: &SolverBody, body2: &SolverBody) -> f32 {
+        let relative_velocity = body2.velocity_at(&self.arm2) - body1.velocity_at(&self.arm1);
+
+        Vec2D::dot(&relative_velocity, &self.axis)
+    }
+
+    /// Auxiliary function that pushes the anchors apart by the impulse
+    fn apply_impulse(&self, body1: &mut SolverBody, body2: &mut SolverBody, impulse: f32) {
+        let impulse_vector = self.axis.mul_n(impulse);
+        body1.apply_impulse(&impulse_vector.mul_n(-1.0), &self.arm1);
+        body2.apply_impulse(&impulse_vector, &self.arm2);
+    }
+}
+
+impl ConstraintInterface for Spring {
+    fn get_objects(&self) -> (ObjectId, ObjectId) {
+        (self.object1, self.object2)
+    }
+
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32) {
+        self.arm1 = body1.get_arm(&self.local_anchor1, 0.0);
+        self.arm2 = body2.get_arm(&self.local_anchor2, 0.0);
+        let distance = body2.position + self.arm2 - body1.position - self.arm1;
+        self.axis = distance.unit();
+
+        let impulse_augmentation1 = Vec2D::cross(&self.arm1, &self.axis);
+        let impulse_augmentation2 = Vec2D::cross(&self.arm2, &self.axis);
+        let inversion_mass = body1.inversion_mass
+            + body2.inversion_mass
+            + impulse_augmentation1 * body1.inversion_inertia * impulse_augmentation1
+            + impulse_augmentation2 * body2.inversion_inertia * impulse_augmentation2;
+
+        // the spring turns the stretch into a velocity, the same way as its force would do it in the step
+        let (gamma, bias_rate) = get_spring_softness(self.stiffness, self.damping, time);
+        self.gamma = gamma;
+        self.bias = (distance.len_vector(&Vec2D::default()) - self.rest_length) * bias_rate;
+        self.soft_mass = if inversion_mass == 0.0 || gamma == 0.0 {
+            0.0
+        } else {
+            1.0 / (inversion_mass + gamma)
+        };
+
+        // applies the impulse continued from the previous step
+        self.apply_impulse(body1, body2, self.impulse);
+    }
+
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody) {
+        let separate_velocity = self.get_separate_velocity(body1, body2);
+        let impulse = -self.soft_mass * (separate_velocity + self.bias + self.gamma * self.impulse);
+        self.impulse += impulse;
+        self.apply_impulse(body1, body2, impulse);
+    }
+
+    fn solve_position(&mut self, _body1: &mut SolverBody, _body2: &mut SolverBody, _solver: &Solver) {
+        // a spring is allowed to stretch, so it has no position error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        map::Map,
+        test_utils::{map_with_gravity, square},
+    };
+
+    /// Auxiliary function that hangs a box of the mass 1 on the spring from the point of the map, the spring is stretched by 10
+    fn hanging_box(stiffness: f32, implicit: bool) -> Map {
+        let mut map = map_with_gravity(Vec2D::new(0.0, 100.0));
+        map.dyn_objects
+            .push(Box::new(square(Vec2D::new(500.0, 410.0), 20.0, 1.0)));
+        let mut spring = Spring::new(
+            ObjectId::World,
+            ObjectId::Dynamic(0),
+            Vec2D::new(500.0, 300.0),
+            Vec2D::default(),
+            100.0,
+            stiffness,
+            1.0,
+        );
+        spring.set_implicit(implicit);
+        map.springs.push(spring);
+
+        map
+    }
+
+    #[test]
+    fn implicit_spring_stays_stable_at_high_stiffness() {
+        // an explicit spring of such stiffness would overshoot more at every step
+        for stiffness in [1.0e4, 1.0e6] {
+            let mut map = hanging_box(stiffness, true);
+            for _ in 0..180 {
+                map.run(1.0 / 60.0);
+                let stretch = map.dyn_objects[0].get_current_position().y - 400.0;
+                assert!(stretch.abs() <= 10.0, "{} {}", stiffness, stretch);
+            }
+
+            // the box rests where the spring holds its weight
+            let dyn_object = &map.dyn_objects[0];
+            let stretch = dyn_object.get_current_position().y - 400.0;
+            assert!((stretch - 100.0 / stiffness).abs() < 0.01, "{} {}", stiffness, stretch);
+            assert!(dyn_object.get_velocity().len_vector(&Vec2D::default()) < 0.1, "{}", stiffness);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{map::Map, objects::rectangle::Rectangle, types::vec2d::Vec2D};
+
+/// Creating a map of 1000 x 1000 surrounded by borders with the gravity
+pub fn map_with_gravity(gravity: Vec2D) -> Map {
+    let mut map = Map::new();
+    map.set_gravity(gravity);
+    map.init_map_border(Vec2D::new(0.0, 0.0), Vec2D::new(1000.0, 1000.0));
+
+    map
+}
+
+/// Creating a resting square box with the friction of 0.5 around the center
+pub fn square(center: Vec2D, size: f32, mass: f32) -> Rectangle {
+    Rectangle::new(
+        Vec2D::new(center.x - size / 2.0, center.y + size / 2.0),
+        Vec2D::new(center.x + size / 2.0, center.y + size / 2.0),
+        size,
+        mass,
+        0.0,
+        Vec2D::default(),
+        0.5,
+        0.0,
+        0.0,
+    )
+}
@@ -0,0 +1,20 @@
+use crate::physics_engine::solver::{ObjectId, Solver, SolverBody};
+
+/// Trait for describing constraints between two objects that are resolved by the solver
+///
+/// Constraints are sent to the threads that resolve islands, so they have to be `Send`
+pub trait ConstraintInterface: Send {
+    /// Returns the objects connected by the constraint
+    fn get_objects(&self) -> (ObjectId, ObjectId);
+
+    /// Function calculates everything that doesn't change during the iterations of the step
+    fn prepare(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, time: f32);
+
+    /// Function changes the velocities of the bodies to satisfy the constraint
+    fn solve_velocity(&mut self, body1: &mut SolverBody, body2: &mut SolverBody);
+
+    /// Function changes the positions of the bodies to reduce the error of the constraint
+    ///
+    /// Only the part of the error given by the solver settings is removed per iteration, so bodies don't pop apart
+    fn solve_position(&mut self, body1: &mut SolverBody, body2: &mut SolverBody, solver: &Solver);
+}
@@ -0,0 +1,9 @@
+use crate::physics_engine::types::vec2d::Vec2D;
+
+/// Trait for describing fields that push dynamic objects
+///
+/// Fields are read by the threads that move objects, so they have to be `Send` and `Sync`
+pub trait ForceFieldInterface: Send + Sync {
+    /// Returns the acceleration given by the field at the position, the force is obtained by scaling it by the mass
+    fn get_acceleration_at(&self, position: &Vec2D) -> Vec2D;
+}
@@ -0,0 +1,12 @@
+use std::any::Any;
+
+use super::constraint_interface::ConstraintInterface;
+
+/// Trait for describing joints, constraints that are added to the map by the user and kept until they are removed
+///
+/// `Any` lets the map return the joint by its handle with its own type, so that it can be changed at runtime
+pub trait JointInterface: ConstraintInterface + Any {
+    /// Objects connected by the joint don't collide with each other unless it is turned on
+    fn set_collide_connected(&mut self, collide_connected: bool);
+    fn get_collide_connected(&self) -> bool;
+}
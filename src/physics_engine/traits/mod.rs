@@ -3,3 +3,9 @@ pub mod as_object;
 pub mod object_interface;
 
 pub mod move_interface;
+
+pub mod constraint_interface;
+
+pub mod force_field_interface;
+
+pub mod joint_interface;
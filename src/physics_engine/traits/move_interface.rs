@@ -18,4 +18,36 @@ pub trait MoveInterface: ObjectInterface {
     /// 
     /// p.s. `MTV(Minimum Translation Vector)` - The minimum translation vector is the shortest distance that the colliding object can be moved in order to no longer be colliding with the collidee. Most of the time, this will be the normal of the face which first impacted
     fn sat(&self, object: &dyn ObjectInterface) -> Option<(f32, Vec2D, Vec2D)>;
+
+    /// Adds the force applied to the center of the object, forces are accumulated until the end of the step
+    fn apply_force(&mut self, force: Vec2D);
+
+    /// Adds the force applied to the point of the map, which also creates a torque
+    fn apply_force_at_point(&mut self, force: Vec2D, point: Vec2D);
+
+    /// Adds the torque, torques are accumulated until the end of the step
+    fn apply_torque(&mut self, torque: f32);
+
+    /// Instantly changes the velocity by the impulse applied to the center of the object
+    fn apply_linear_impulse(&mut self, impulse: Vec2D);
+
+    /// Instantly changes the velocity and the angle velocity by the impulse applied to the point of the map
+    fn apply_impulse_at_point(&mut self, impulse: Vec2D, point: Vec2D);
+
+    fn get_force(&self) -> Vec2D;
+    fn get_torque(&self) -> f32;
+
+    /// Function removes the accumulated forces and torques
+    fn clear_forces(&mut self);
+
+    /// Sleeping objects are not moved and not checked for collisions with each other until they are woken
+    fn set_sleeping(&mut self, sleeping: bool);
+    fn is_sleeping(&self) -> bool;
+
+    /// Time during which the object has been moving slower than the sleep thresholds of the map
+    fn set_sleep_time(&mut self, sleep_time: f32);
+    fn get_sleep_time(&self) -> f32;
+
+    /// Function wakes the object, the map wakes the rest of its island at the next step
+    fn wake(&mut self);
 }
@@ -1,8 +1,11 @@
-use crate::physics_engine::types::{angle::Angle, vec2d::Vec2D};
+use crate::physics_engine::types::{angle::Angle, material::Material, vec2d::Vec2D};
 
 use super::as_object::AsObject;
 
-pub trait ObjectInterface: AsObject {
+/// Trait for describing objects of the map
+///
+/// Objects are moved and checked for collisions by several threads, so they have to be `Send` and `Sync`
+pub trait ObjectInterface: AsObject + Send + Sync {
     fn set_current_position(&mut self, position: Vec2D);
     fn get_current_position(&self) -> Vec2D;
     fn set_potential_position(&mut self, position: Vec2D);
@@ -22,14 +25,28 @@ pub trait ObjectInterface: AsObject {
     fn get_elasticity(&self) -> f32;
     fn set_velocity(&mut self, velocity: Vec2D);
     fn get_velocity(&self) -> Vec2D;
+    /// Friction coefficient of the surface in contacts, it sets both the static and the dynamic friction of the material
     fn set_friction(&mut self, friction: f32);
     fn get_friction(&self) -> f32;
+    /// Damping of the velocity per second, so it doesn't depend on the length of the step
+    fn set_linear_damping(&mut self, linear_damping: f32);
+    fn get_linear_damping(&self) -> f32;
     fn set_angle(&mut self, angle: Angle);
     fn get_angle(&self) -> Angle;
     fn set_angle_velocity(&mut self, angle_velocity: f32);
     fn get_angle_velocity(&self) -> f32;
+    /// Damping of the angle velocity per second, a damping of d per step of 1/60 second corresponds to -60 ln(1 - d)
     fn set_angle_friction(&mut self, angle_friction: f32);
     fn get_angle_friction(&self) -> f32;
+    /// Material with separate static and dynamic friction, the friction of a contact is the geometric mean of both materials
+    fn set_material(&mut self, material: Material);
+    fn get_material(&self) -> Material;
+    /// Drag coefficient of the shape in the air, zero makes the object unaffected by the air and the wind
+    fn set_drag(&mut self, drag: f32);
+    fn get_drag(&self) -> f32;
+    /// Speed with which the surface moves along the direction of the object, e.g. a conveyor belt, it carries touching objects by the friction
+    fn set_surface_velocity(&mut self, surface_velocity: f32);
+    fn get_surface_velocity(&self) -> f32;
     fn get_circumradius(&self) -> f32;
     fn get_axis(&self) -> Vec<Vec2D>;
     fn projection_on_axis(&self, axis: &Vec2D) -> (f32, f32, Vec2D);
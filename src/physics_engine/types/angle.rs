@@ -40,6 +40,15 @@ impl Angle {
 
         self.radian = filtered_angle
     }
+
+    /// Returns the angle in the range from -π to π, so that turns in both directions can be compared
+    pub fn get_signed_radian(&self) -> f32 {
+        if self.radian > PI {
+            self.radian - 2.0 * PI
+        } else {
+            self.radian
+        }
+    }
 }
 
 
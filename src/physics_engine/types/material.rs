@@ -0,0 +1,46 @@
+/// Structure describing the surface of an object
+#[derive(Debug, Copy, Clone)]
+pub struct Material {
+    static_friction: f32,
+    dynamic_friction: f32,
+}
+
+impl Material {
+    /// Creating a material
+    pub fn new(static_friction: f32, dynamic_friction: f32) -> Self {
+        Material {
+            static_friction,
+            dynamic_friction,
+        }
+    }
+
+    pub fn set_static_friction(&mut self, static_friction: f32) {
+        self.static_friction = static_friction;
+    }
+
+    pub fn get_static_friction(&self) -> f32 {
+        self.static_friction
+    }
+
+    pub fn set_dynamic_friction(&mut self, dynamic_friction: f32) {
+        self.dynamic_friction = dynamic_friction;
+    }
+
+    pub fn get_dynamic_friction(&self) -> f32 {
+        self.dynamic_friction
+    }
+
+    /// Creates the material of the contact of two surfaces, the coefficients are combined by the geometric mean
+    pub fn combine(&self, material: &Material) -> Material {
+        Material::new(
+            (self.static_friction * material.static_friction).sqrt(),
+            (self.dynamic_friction * material.dynamic_friction).sqrt(),
+        )
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new(0.5, 0.3)
+    }
+}
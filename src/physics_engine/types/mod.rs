@@ -3,3 +3,7 @@ pub mod vec2d;
 pub mod matrix2d;
 
 pub mod angle;
+
+pub mod material;
+
+pub mod region;
@@ -0,0 +1,22 @@
+use super::vec2d::Vec2D;
+
+/// Area of the map that limits the action of something
+#[derive(Debug, Copy, Clone)]
+pub enum Region {
+    /// Axis-aligned rectangle given by the left top and the right bottom points
+    Aabb(Vec2D, Vec2D),
+    /// Circle given by the center and the radius
+    Circle(Vec2D, f32),
+}
+
+impl Region {
+    /// Checks if the point is inside the region
+    pub fn contains(&self, point: &Vec2D) -> bool {
+        match self {
+            Region::Aabb(plt, prb) => {
+                (plt.x..=prb.x).contains(&point.x) && (plt.y..=prb.y).contains(&point.y)
+            }
+            Region::Circle(center, radius) => center.len_vector(point) <= *radius,
+        }
+    }
+}
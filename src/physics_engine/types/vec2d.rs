@@ -34,7 +34,7 @@ impl Vec2D {
         let parallelism = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
 
         match parallelism {
-            parallelism if parallelism == 0.0 => None,
+            0.0 => None,
             _ => {
                 let x = ((x1 * y2 - y1 * x2) * (x3 - x4) - (x1 - x2) * (x3 * y4 - y3 * x4))
                     / parallelism;
@@ -73,7 +73,7 @@ impl Vec2D {
         let parallelism = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
 
         match parallelism {
-            parallelism if parallelism == 0.0 => None,
+            0.0 => None,
             _ => {
                 let x = ((x1 * y2 - y1 * x2) * (x3 - x4) - (x1 - x2) * (x3 * y4 - y3 * x4))
                     / parallelism;
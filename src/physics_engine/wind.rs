@@ -0,0 +1,106 @@
+use super::types::{region::Region, vec2d::Vec2D};
+
+/// Movement of the air that carries objects with drag
+///
+/// Unlike force fields the wind gives a velocity, so it pushes only the objects that move slower than the air
+pub struct Wind {
+    velocity: Vec2D,
+    region: Option<Region>,
+}
+
+impl Wind {
+    /// Creating a wind, without a region it blows over the whole map
+    pub fn new(velocity: Vec2D, region: Option<Region>) -> Self {
+        Wind { velocity, region }
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vec2D) {
+        self.velocity = velocity;
+    }
+
+    pub fn get_velocity(&self) -> Vec2D {
+        self.velocity
+    }
+
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+    }
+
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+
+    /// Returns the velocity of the air at the position
+    pub fn get_velocity_at(&self, position: &Vec2D) -> Vec2D {
+        match self.region {
+            Some(region) if !region.contains(position) => Vec2D::default(),
+            _ => self.velocity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics_engine::{
+        objects::rectangle::Rectangle,
+        test_utils::{map_with_gravity, square},
+        traits::object_interface::ObjectInterface,
+    };
+
+    #[test]
+    fn wind_carries_the_objects_in_its_region_up_to_its_velocity() {
+        let mut map = map_with_gravity(Vec2D::default());
+        map.winds.push(Wind::new(
+            Vec2D::new(100.0, 0.0),
+            Some(Region::Aabb(Vec2D::new(0.0, 0.0), Vec2D::new(1000.0, 500.0))),
+        ));
+        for y in [300.0, 700.0] {
+            let mut dyn_object = square(Vec2D::new(100.0, y), 20.0, 1.0);
+            dyn_object.set_drag(0.001);
+            map.dyn_objects.push(Box::new(dyn_object));
+        }
+
+        for _ in 0..120 {
+            map.run(1.0 / 60.0);
+            let velocity = map.dyn_objects[0].get_velocity();
+            assert!(velocity.x <= 100.0 && velocity.y.abs() < 0.001, "{:?}", velocity);
+        }
+
+        // the object under the region stays in the still air
+        assert!(map.dyn_objects[0].get_velocity().x > 50.0);
+        assert!(map.dyn_objects[1].get_velocity().len_vector(&Vec2D::default()) == 0.0);
+    }
+
+    #[test]
+    fn drag_slows_down_a_plank_moving_broadside_more() {
+        let mut map = map_with_gravity(Vec2D::default());
+        for (y, velocity) in [(300.0, Vec2D::new(0.0, 100.0)), (700.0, Vec2D::new(100.0, 0.0))] {
+            let mut plank = Rectangle::new(
+                Vec2D::new(80.0, y + 2.0),
+                Vec2D::new(120.0, y + 2.0),
+                4.0,
+                1.0,
+                0.0,
+                velocity,
+                0.5,
+                0.0,
+                0.0,
+            );
+            plank.set_drag(0.001);
+            map.dyn_objects.push(Box::new(plank));
+        }
+        for _ in 0..60 {
+            map.run(1.0 / 60.0);
+        }
+
+        // the drag goes against the velocity and follows dv/dt = -k v², where k grows with the width across the flow
+        let broadside = map.dyn_objects[0].get_velocity();
+        let lengthwise = map.dyn_objects[1].get_velocity();
+        for (velocity, width) in [(broadside.y, 40.0), (lengthwise.x, 4.0)] {
+            let k = 0.5 * 0.001 * width;
+            assert!((velocity - 100.0 / (1.0 + k * 100.0)).abs() < 1.0, "{} {}", width, velocity);
+        }
+        assert!(broadside.x == 0.0 && lengthwise.y == 0.0);
+    }
+}